@@ -2,6 +2,20 @@
 
 use core::ops;
 use std::cmp::min;
+use std::fmt;
+
+mod memory;
+#[cfg(feature = "serde")]
+mod serialization;
+mod width;
+
+pub use memory::*;
+#[cfg(feature = "serde")]
+pub use serde;
+#[cfg(feature = "serde")]
+pub use serialization::serde_array;
+use width::{MAX_WORDS, from_array, to_array};
+pub use width::{SupportedWidth, Width, Words};
 
 // NOTE: Ideally we would use unbounded integers for natural numbers. Yet in practice this would
 // mess up with things such as the SMT solver during symbolic execution.
@@ -9,8 +23,6 @@ use std::cmp::min;
 // a better solution is needed.
 pub type nat = u128;
 
-pub fn sail_branch_announce(_value: i128, _pc: BitVector<64>) {}
-
 pub fn lteq_int(e1: i128, e2: i128) -> bool {
     e1 <= e2
 }
@@ -19,24 +31,73 @@ pub fn gt_int(e1: i128, e2: i128) -> bool {
     e1 > e2
 }
 
-pub fn bitvector_length<const N: i128>(_e: BitVector<N>) -> i128 {
+pub fn bitvector_length<const N: i128>(_e: BitVector<N>) -> i128
+where
+    Width<N>: SupportedWidth,
+{
     N
 }
 
-pub fn parse_hex_bits<const N: i128>(_n: i128, _hex_str: &str) -> BitVector<N> {
-    todo!("'parse_hex_bits' is not yet implemented");
+/// Parse a `0x` prefixed hexadecimal string into a bitvector of `n` bits.
+///
+/// As in the C runtime of Sail, malformed strings and values that do not fit return zero.
+pub fn parse_hex_bits<const N: i128>(n: i128, hex_str: String) -> BitVector<N>
+where
+    Width<N>: SupportedWidth,
+{
+    let digits: Option<Vec<u64>> = match hex_str.strip_prefix("0x") {
+        Some(digits) if !digits.is_empty() => digits
+            .chars()
+            .map(|digit| digit.to_digit(16).map(u64::from))
+            .collect(),
+        _ => None,
+    };
+    let Some(mut digits) = digits else {
+        return BitVector::new_empty();
+    };
+
+    digits.reverse();
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+    let width = match digits.last() {
+        Some(msd) => 4 * (digits.len() as i128 - 1) + (64 - msd.leading_zeros()) as i128,
+        None => 0,
+    };
+    if width > n.min(N) {
+        return BitVector::new_empty();
+    }
+
+    let mut words = vec![0; digits.len().div_ceil(16)];
+    for (i, digit) in digits.into_iter().enumerate() {
+        words[i / 16] |= digit << (4 * (i % 16));
+    }
+    BitVector::from_words(&words)
 }
 
 pub fn bitvector_concat<const N: i128, const M: i128, const NM: i128>(
     e1: BitVector<N>,
     e2: BitVector<M>,
-) -> BitVector<{ NM }> {
-    bv::<{ NM }>((e1.bits() << M) | e2.bits())
+) -> BitVector<{ NM }>
+where
+    Width<N>: SupportedWidth,
+    Width<M>: SupportedWidth,
+    Width<NM>: SupportedWidth,
+{
+    if NM <= 64 {
+        let high = e1.bits().checked_shl(M as u32).unwrap_or(0);
+        return bv::<{ NM }>(high | e2.bits());
+    }
+
+    (e1.resize::<NM>() << (M as usize)) | e2.resize::<NM>()
 }
 
-pub fn get_slice_int<const L: i128>(l: i128, n: i128, start: i128) -> BitVector<L> {
-    let val = (n >> start) & (mask(l as usize) as i128);
-    bv(val as u64)
+pub fn get_slice_int<const L: i128>(l: i128, n: i128, start: i128) -> BitVector<L>
+where
+    Width<L>: SupportedWidth,
+{
+    let val = (n >> start) as u128 & u128_mask(l as usize);
+    BitVector::from_u128(val)
 }
 
 pub fn get_16_random_bits(_unit: ()) -> BitVector<16> {
@@ -51,7 +112,10 @@ pub fn internal_error(_file: String, _line: i128, _s: String) -> ! {
     panic!("Softcore: internal error")
 }
 
-pub fn print_output<const N: i128>(text: String, _csr: BitVector<N>) {
+pub fn print_output<const N: i128>(text: String, _csr: BitVector<N>)
+where
+    Width<N>: SupportedWidth,
+{
     println!("{}", text)
 }
 
@@ -59,12 +123,19 @@ pub fn print_platform(text: String) {
     println!("{}", text)
 }
 
-pub fn bits_str<const N: i128>(val: BitVector<N>) -> String {
-    format!("{:b}", val.bits())
+pub fn bits_str<const N: i128>(val: BitVector<N>) -> String
+where
+    Width<N>: SupportedWidth,
+{
+    format!("{:b}", val)
 }
 
-pub fn bitvector_access<const N: i128>(vec: BitVector<N>, idx: i128) -> bool {
-    (vec.bits() & (1 << idx)) > 0
+pub fn bitvector_access<const N: i128>(vec: BitVector<N>, idx: i128) -> bool
+where
+    Width<N>: SupportedWidth,
+{
+    let idx = idx as usize;
+    (vec.words()[idx / 64] >> (idx % 64)) & 1 == 1
 }
 
 // Todo: implement truncate for other sizes if required
@@ -73,21 +144,27 @@ pub fn truncate(v: BitVector<64>, size: i128) -> BitVector<64> {
     v
 }
 
-pub fn sign_extend<const M: i128>(value: i128, input: BitVector<M>) -> BitVector<64> {
-    assert!(
-        value == 64,
-        "handle the case where sign_extend has value not equal 64"
-    );
-    let extension = ((1 << (64 - M)) - 1) << M;
-    bv::<64>(extension | input.bits)
+pub fn sign_extend<const M: i128, const N: i128>(value: i128, input: BitVector<M>) -> BitVector<N>
+where
+    Width<M>: SupportedWidth,
+    Width<N>: SupportedWidth,
+{
+    assert_eq!(value, N, "Invalid sign-extension width");
+    input.sign_extend::<N>()
 }
 
-pub const fn sail_ones<const N: i128>(n: i128) -> BitVector<N> {
-    assert!(n <= 64);
-    bv::<N>(mask(n as usize))
+pub const fn sail_ones<const N: i128>(n: i128) -> BitVector<N>
+where
+    Width<N>: SupportedWidth,
+{
+    assert!(n <= N);
+    BitVector::from_array(ones(n as usize))
 }
 
-pub const fn sail_zeros<const N: i128>(_n: i128) -> BitVector<N> {
+pub const fn sail_zeros<const N: i128>(_n: i128) -> BitVector<N>
+where
+    Width<N>: SupportedWidth,
+{
     bv::<N>(0)
 }
 
@@ -99,23 +176,34 @@ pub fn cancel_reservation(_unit: ()) {
     // In the future, extend this function
 }
 
-pub fn hex_bits_12_forwards(_reg: BitVector<12>) -> ! {
-    todo!("Implement this function")
+pub fn hex_bits_forwards<const N: i128>(_n: i128, bits: BitVector<N>) -> String
+where
+    Width<N>: SupportedWidth,
+{
+    format!("{:#x}", bits)
 }
 
-pub fn hex_bits_12_backwards(_: &'static str) -> BitVector<12> {
-    todo!("Implement this function")
+pub fn hex_bits_12_forwards(reg: BitVector<12>) -> String {
+    hex_bits_forwards(12, reg)
+}
+
+pub fn hex_bits_12_backwards(hex: String) -> BitVector<12> {
+    parse_hex_bits(12, hex)
 }
 
 pub fn subrange_bits<const IN: i128, const OUT: i128>(
     vec: BitVector<IN>,
     end: i128,
     start: i128,
-) -> BitVector<OUT> {
+) -> BitVector<OUT>
+where
+    Width<IN>: SupportedWidth,
+    Width<OUT>: SupportedWidth,
+{
     assert_eq!((end - start + 1), OUT);
     assert!(OUT <= IN);
 
-    bv((vec.bits >> start) & mask(OUT as usize))
+    (vec >> (start as usize)).resize()
 }
 
 pub fn update_subrange_bits<const N: i128, const M: i128>(
@@ -123,39 +211,65 @@ pub fn update_subrange_bits<const N: i128, const M: i128>(
     to: u64,
     from: u64,
     value: BitVector<M>,
-) -> BitVector<N> {
+) -> BitVector<N>
+where
+    Width<N>: SupportedWidth,
+    Width<M>: SupportedWidth,
+{
     assert!(to - from + 1 == M as u64, "size don't match");
 
-    // Generate the 111111 mask
-    let mut mask = (1 << M) - 1;
-    // Shit and invert it
-    mask = !(mask << from);
+    // Generate the 111111 mask, shifted to the updated range
+    let mask = sail_ones::<N>(M) << (from as usize);
+    let value = value.resize::<N>() << (from as usize);
 
     // Now we can update and return the updated value
-    bv((bits.bits & mask) | (value.bits() << from))
+    (bits & !mask) | value
 }
 
-pub fn bitvector_update<const N: i128>(v: BitVector<N>, pos: i128, value: bool) -> BitVector<N> {
-    let mask = 1 << pos;
-    bv((v.bits() & !mask) | (value as u64) << pos)
+pub fn bitvector_update<const N: i128>(v: BitVector<N>, pos: i128, value: bool) -> BitVector<N>
+where
+    Width<N>: SupportedWidth,
+{
+    v.set_bit(pos, value)
 }
 
-#[derive(Eq, PartialEq, Clone, Copy, Debug, Default)]
-pub struct BitVector<const N: i128> {
-    bits: u64,
+/// The maximum width of a [BitVector], in bits.
+pub const MAX_BITVECTOR_WIDTH: i128 = 512;
+
+/// A bitvector of width N.
+///
+/// Bitvectors of up to [MAX_BITVECTOR_WIDTH] bits are supported. Bitvectors of up to 64 bits are
+/// stored in a single `u64`, wider bitvectors are stored inline as little-endian 64 bits words
+/// (see [SupportedWidth]).
+#[derive(Eq, PartialEq, Clone, Copy, Default)]
+pub struct BitVector<const N: i128>
+where
+    Width<N>: SupportedWidth,
+{
+    /// Invariant: the bits above N are always zero.
+    words: <Width<N> as SupportedWidth>::Words,
 }
 
 #[derive(Eq, PartialEq, Clone, Copy, Debug, Default)]
-pub struct BitField<const T: i128> {
+pub struct BitField<const T: i128>
+where
+    Width<T>: SupportedWidth,
+{
     pub bits: BitVector<T>,
 }
 
-impl<const N: i128> BitField<N> {
+impl<const N: i128> BitField<N>
+where
+    Width<N>: SupportedWidth,
+{
     pub const fn new(value: u64) -> Self {
         BitField { bits: bv(value) }
     }
 
-    pub const fn subrange<const A: i128, const B: i128, const C: i128>(self) -> BitVector<C> {
+    pub const fn subrange<const A: i128, const B: i128, const C: i128>(self) -> BitVector<C>
+    where
+        Width<C>: SupportedWidth,
+    {
         assert!(B - A == C, "Invalid subrange parameters");
         assert!(B <= N, "Invalid subrange");
 
@@ -165,7 +279,10 @@ impl<const N: i128> BitField<N> {
     pub const fn set_subrange<const A: i128, const B: i128, const C: i128>(
         self,
         bitvector: BitVector<C>,
-    ) -> Self {
+    ) -> Self
+    where
+        Width<C>: SupportedWidth,
+    {
         assert!(B - A == C, "Invalid subrange parameters");
         assert!(A <= B && B <= N, "Invalid subrange");
 
@@ -175,52 +292,171 @@ impl<const N: i128> BitField<N> {
     }
 }
 
-impl<const N: i128> PartialOrd for BitVector<N> {
+impl<const N: i128> PartialOrd for BitVector<N>
+where
+    Width<N>: SupportedWidth,
+{
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.bits.partial_cmp(&other.bits)
+        // Compare the most significant words first
+        self.words()
+            .iter()
+            .rev()
+            .partial_cmp(other.words().iter().rev())
+    }
+}
+
+impl<const N: i128> fmt::Debug for BitVector<N>
+where
+    Width<N>: SupportedWidth,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BitVector<{}>({:#x})", N, self)
+    }
+}
+
+impl<const N: i128> fmt::Binary for BitVector<N>
+where
+    Width<N>: SupportedWidth,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "0b")?;
+        }
+        let words = self.significant_words();
+        let (top, rest) = words.split_last().unwrap_or((&0, &[]));
+        write!(f, "{:b}", top)?;
+        for word in rest.iter().rev() {
+            write!(f, "{:064b}", word)?;
+        }
+        Ok(())
+    }
+}
+
+impl<const N: i128> fmt::LowerHex for BitVector<N>
+where
+    Width<N>: SupportedWidth,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "0x")?;
+        }
+        let words = self.significant_words();
+        let (top, rest) = words.split_last().unwrap_or((&0, &[]));
+        write!(f, "{:x}", top)?;
+        for word in rest.iter().rev() {
+            write!(f, "{:016x}", word)?;
+        }
+        Ok(())
     }
 }
 
 /// Create a fresh [BitVector].
 ///
 /// This is equivalent to [BitVector::new], with a shorted syntax.
-pub const fn bv<const N: i128>(val: u64) -> BitVector<N> {
+pub const fn bv<const N: i128>(val: u64) -> BitVector<N>
+where
+    Width<N>: SupportedWidth,
+{
     BitVector::new(val)
 }
 
-impl<const N: i128> BitVector<N> {
+impl<const N: i128> BitVector<N>
+where
+    Width<N>: SupportedWidth,
+{
+    /// The number of words backing the bitvector.
+    const LEN: usize = if N <= 64 {
+        1
+    } else {
+        (N as usize).div_ceil(64)
+    };
+
     pub const fn new(val: u64) -> Self {
-        if N < 64 {
-            Self {
-                bits: val & ((1 << N) - 1),
-            }
-        } else {
-            Self { bits: val }
-        }
+        let mut words = [0; MAX_WORDS];
+        words[0] = val;
+        Self::from_array(words)
     }
 
     pub const fn new_empty() -> Self {
-        Self { bits: 0 }
+        Self::from_array([0; MAX_WORDS])
     }
 
+    /// Creates a bitvector from a 128 bits value, truncated to N bits.
+    pub const fn from_u128(val: u128) -> Self {
+        let mut words = [0; MAX_WORDS];
+        words[0] = val as u64;
+        words[1] = (val >> 64) as u64;
+        Self::from_array(words)
+    }
+
+    /// Creates a bitvector from little-endian 64 bits words, truncated to N bits.
+    ///
+    /// Missing words are zero.
+    pub fn from_words(words: &[u64]) -> Self {
+        let mut bitvector = Self::new_empty();
+        for (word, value) in bitvector.words.as_mut_slice().iter_mut().zip(words) {
+            *word = *value;
+        }
+        bitvector.truncate()
+    }
+
+    /// Returns the bits as little-endian 64 bits words.
+    pub fn words(&self) -> &[u64] {
+        self.words.as_slice()
+    }
+
+    /// Returns the 64 least significant bits.
     pub const fn bits(self) -> u64 {
-        self.bits
+        self.to_array()[0]
+    }
+
+    /// Returns the 128 least significant bits.
+    pub const fn bits_u128(self) -> u128 {
+        let words = self.to_array();
+        (words[1] as u128) << 64 | words[0] as u128
     }
 
     /// Get the bits as an integer.
     ///
-    /// The bitvector is interpreted as unsigned.
+    /// The bitvector is interpreted as unsigned, panics if the value does not fit in an i128.
     pub const fn unsigned(self) -> i128 {
-        // Note that bits is unsigned, so converting to a bigger i128 guarantees the result is
-        // still positive.
-        self.bits as i128
+        let words = self.to_array();
+        let mut i = 2;
+        while i < Self::LEN {
+            assert!(words[i] == 0, "The value does not fit in an i128");
+            i += 1;
+        }
+
+        let value = self.bits_u128();
+        assert!(
+            value <= i128::MAX as u128,
+            "The value does not fit in an i128"
+        );
+        value as i128
     }
 
     /// Get the bits as an integer.
     ///
-    /// The bitvector is interpreted as signed
+    /// The bitvector is interpreted as signed, panics if the value does not fit in an i128.
     pub const fn signed(self) -> i128 {
-        let value = self.bits as u128;
+        if N > 128 {
+            // The bits above the 127th must all be copies of the sign bit
+            let high = self.shr_const(127);
+            let negative = high.to_array()[0] & 1 == 1;
+            let expected = if negative {
+                sail_ones::<N>(N - 127)
+            } else {
+                Self::new_empty()
+            };
+            assert!(high.eq_const(expected), "The value does not fit in an i128");
+            return self.bits_u128() as i128;
+        }
+
+        let value = self.bits_u128();
+        if N == 128 {
+            return value as i128;
+        }
+
         let sign_bit_mask = 1 << (N - 1);
         if value & sign_bit_mask == 0 {
             // The number is positive, nothing to do
@@ -233,189 +469,425 @@ impl<const N: i128> BitVector<N> {
     }
 
     pub const fn as_usize(self) -> usize {
-        self.bits as usize
+        self.bits() as usize
     }
 
     pub const fn as_i128(self) -> i128 {
-        self.bits as i128
+        self.bits_u128() as i128
     }
 
-    pub const fn zero_extend<const M: i128>(self) -> BitVector<M> {
+    pub const fn zero_extend<const M: i128>(self) -> BitVector<M>
+    where
+        Width<M>: SupportedWidth,
+    {
         assert!(M >= N, "Can not zero-extend to a smaller size!");
-        assert!(M <= 64, "Maximum zero-extend supported size if 64");
 
-        // Here we have nothing to do, we already use 64 bits with zeroes for MSBs
-        BitVector { bits: self.bits }
+        // Here we have nothing to do, the bits above N are already zeroes
+        self.resize::<M>()
+    }
+
+    pub fn sign_extend<const M: i128>(self) -> BitVector<M>
+    where
+        Width<M>: SupportedWidth,
+    {
+        assert!(M >= N, "Can not sign-extend to a smaller size!");
+
+        let extended = self.resize::<M>();
+        if N > 0 && bitvector_access(self, N - 1) {
+            extended | !sail_ones::<M>(N)
+        } else {
+            extended
+        }
+    }
+
+    /// Returns the bitvector with a different width, truncating it if necessary.
+    const fn resize<const M: i128>(self) -> BitVector<M>
+    where
+        Width<M>: SupportedWidth,
+    {
+        BitVector::from_array(self.to_array())
     }
 
     pub fn set_bit(self, idx: i128, value: bool) -> Self {
         assert!(idx < N, "Out of bounds array check");
-        let new_value = if value {
-            self.bits | 1u64 << idx
+        let mut result = self;
+        let words = result.words.as_mut_slice();
+        let (word, bit) = (idx as usize / 64, idx as usize % 64);
+        if value {
+            words[word] |= 1u64 << bit
         } else {
-            self.bits & !(1u64 << idx)
+            words[word] &= !(1u64 << bit)
         };
-        BitVector { bits: new_value }
+        result
     }
 
-    pub const fn subrange<const A: i128, const B: i128, const C: i128>(self) -> BitVector<C> {
+    pub const fn subrange<const A: i128, const B: i128, const C: i128>(self) -> BitVector<C>
+    where
+        Width<C>: SupportedWidth,
+    {
         assert!(B - A == C, "Invalid subrange parameters");
         assert!(B <= N, "Invalid subrange");
 
-        let mut val = self.bits; // The current value
-        val &= BitVector::<B>::bit_mask(); // Remove top bits
-        val >>= A; // Shift all the bits
-        bv(val)
+        if B <= 64 {
+            let mut val = self.bits(); // The current value
+            val &= mask(B as usize); // Remove top bits
+            val >>= A; // Shift all the bits
+            return bv(val);
+        }
+
+        self.shr_const(A as usize).resize::<C>()
     }
 
     pub const fn set_subrange<const A: i128, const B: i128, const C: i128>(
         self,
         bits: BitVector<C>,
-    ) -> Self {
+    ) -> Self
+    where
+        Width<C>: SupportedWidth,
+    {
         assert!(B - A == C, "Invalid set_subrange parameters");
         assert!(B <= N, "Invalid subrange");
 
-        let mask = !(BitVector::<C>::bit_mask() << A);
-        let new_bits = bits.bits() << A;
-        bv((self.bits & mask) | new_bits)
+        if N <= 64 {
+            let mask = !(mask(C as usize) << A);
+            let new_bits = bits.bits() << A;
+            return bv((self.bits() & mask) | new_bits);
+        }
+
+        let mask = shift_left(ones(C as usize), A as usize);
+        let new_bits = shift_left(bits.to_array(), A as usize);
+        let mut words = self.to_array();
+        let mut i = 0;
+        while i < Self::LEN {
+            words[i] = (words[i] & !mask[i]) | new_bits[i];
+            i += 1;
+        }
+        Self::from_array(words)
     }
 
     pub const fn wrapped_add(self, other: BitVector<N>) -> BitVector<N> {
-        bv::<N>(self.bits.wrapping_add(other.bits))
+        if N <= 64 {
+            return bv::<N>(self.bits().wrapping_add(other.bits()));
+        }
+
+        Self::from_array(add(self.to_array(), other.to_array()))
     }
 
     /// Returns a bit mask with 1 for the first [N] bits.
     const fn bit_mask() -> u64 {
         assert!(N <= 64);
 
-        if N == 64 { u64::MAX } else { (1 << N) - 1 }
+        mask(N as usize)
+    }
+
+    /// Creates a bitvector from the first words of the array, truncated to N bits.
+    const fn from_array(mut words: [u64; MAX_WORDS]) -> Self {
+        let top = Self::LEN - 1;
+        words[top] &= mask((N - 64 * top as i128) as usize);
+        Self {
+            words: from_array(words),
+        }
+    }
+
+    /// Returns the words of the bitvector, zero-extended to the width of the widest bitvectors.
+    const fn to_array(self) -> [u64; MAX_WORDS] {
+        to_array(self.words)
+    }
+
+    /// Clears the bits above N.
+    fn truncate(mut self) -> Self {
+        let top = Self::LEN - 1;
+        self.words.as_mut_slice()[top] &= mask((N - 64 * top as i128) as usize);
+        self
+    }
+
+    /// Returns the words up to the most significant non-zero word.
+    fn significant_words(&self) -> &[u64] {
+        let words = self.words();
+        let len = words
+            .iter()
+            .rposition(|word| *word != 0)
+            .map_or(0, |i| i + 1);
+        &words[..len]
+    }
+
+    /// Applies an operation word by word.
+    #[inline]
+    fn map_words(mut self, rhs: Self, op: impl Fn(u64, u64) -> u64) -> Self {
+        let words = self.words.as_mut_slice().iter_mut();
+        for (word, rhs) in words.zip(rhs.words()) {
+            *word = op(*word, *rhs);
+        }
+        self
+    }
+
+    /// A right shift usable in const functions.
+    const fn shr_const(self, shift: usize) -> Self {
+        Self::from_array(shift_right(self.to_array(), shift))
+    }
+
+    /// An equality test usable in const functions.
+    const fn eq_const(self, other: Self) -> bool {
+        let (a, b) = (self.to_array(), other.to_array());
+        let mut i = 0;
+        while i < Self::LEN {
+            if a[i] != b[i] {
+                return false;
+            }
+            i += 1;
+        }
+        true
     }
 }
 
-impl<const N: i128> ops::BitAnd for BitVector<N> {
+impl<const N: i128> ops::BitAnd for BitVector<N>
+where
+    Width<N>: SupportedWidth,
+{
     type Output = Self;
 
     fn bitand(self, rhs: Self) -> Self::Output {
-        Self {
-            bits: self.bits & rhs.bits,
-        }
+        self.map_words(rhs, |a, b| a & b)
     }
 }
 
-impl<const N: i128> ops::BitOr for BitVector<N> {
+impl<const N: i128> ops::BitOr for BitVector<N>
+where
+    Width<N>: SupportedWidth,
+{
     type Output = Self;
 
     fn bitor(self, rhs: Self) -> Self::Output {
-        Self {
-            bits: self.bits | rhs.bits,
-        }
+        self.map_words(rhs, |a, b| a | b)
     }
 }
 
-impl<const N: i128> ops::BitXor for BitVector<N> {
+impl<const N: i128> ops::BitXor for BitVector<N>
+where
+    Width<N>: SupportedWidth,
+{
     type Output = Self;
 
     fn bitxor(self, rhs: Self) -> Self::Output {
-        Self {
-            bits: self.bits ^ rhs.bits,
-        }
+        self.map_words(rhs, |a, b| a ^ b)
     }
 }
 
-impl<const N: i128> ops::Shl<usize> for BitVector<N> {
+impl<const N: i128> ops::Shl<usize> for BitVector<N>
+where
+    Width<N>: SupportedWidth,
+{
     type Output = Self;
 
     fn shl(self, rhs: usize) -> Self::Output {
-        Self {
-            bits: self.bits << rhs,
+        if N <= 64 {
+            return if rhs < 64 {
+                bv(self.bits() << rhs)
+            } else {
+                bv(0)
+            };
         }
+
+        Self::from_array(shift_left(self.to_array(), rhs))
     }
 }
 
-impl<const N: i128> ops::Shl<i128> for BitVector<N> {
+impl<const N: i128> ops::Shl<i128> for BitVector<N>
+where
+    Width<N>: SupportedWidth,
+{
     type Output = Self;
 
     fn shl(self, rhs: i128) -> Self::Output {
-        Self {
-            bits: self.bits << rhs,
-        }
+        self << (rhs as usize)
     }
 }
 
-impl<const N: i128> ops::Shl<i32> for BitVector<N> {
+impl<const N: i128> ops::Shl<i32> for BitVector<N>
+where
+    Width<N>: SupportedWidth,
+{
     type Output = Self;
 
     fn shl(self, rhs: i32) -> Self::Output {
-        Self {
-            bits: self.bits << rhs,
-        }
+        self << (rhs as usize)
     }
 }
 
-impl<const N: i128> ops::Shr<usize> for BitVector<N> {
+impl<const N: i128> ops::Shr<usize> for BitVector<N>
+where
+    Width<N>: SupportedWidth,
+{
     type Output = Self;
 
     fn shr(self, rhs: usize) -> Self::Output {
-        Self {
-            bits: self.bits >> rhs,
+        if N <= 64 {
+            return if rhs < 64 {
+                bv(self.bits() >> rhs)
+            } else {
+                bv(0)
+            };
         }
+
+        self.shr_const(rhs)
     }
 }
 
-impl<const N: i128> ops::Shr<i128> for BitVector<N> {
+impl<const N: i128> ops::Shr<i128> for BitVector<N>
+where
+    Width<N>: SupportedWidth,
+{
     type Output = Self;
 
     fn shr(self, rhs: i128) -> Self::Output {
-        Self {
-            bits: self.bits >> rhs,
-        }
+        self >> (rhs as usize)
     }
 }
 
-impl<const N: i128> ops::Shr<i32> for BitVector<N> {
+impl<const N: i128> ops::Shr<i32> for BitVector<N>
+where
+    Width<N>: SupportedWidth,
+{
     type Output = Self;
 
     fn shr(self, rhs: i32) -> Self::Output {
-        Self {
-            bits: self.bits >> rhs,
-        }
+        self >> (rhs as usize)
     }
 }
 
-impl<const N: i128> ops::Not for BitVector<N> {
+impl<const N: i128> ops::Not for BitVector<N>
+where
+    Width<N>: SupportedWidth,
+{
     type Output = Self;
 
-    fn not(self) -> Self::Output {
-        bv((!self.bits) & Self::bit_mask())
+    fn not(mut self) -> Self::Output {
+        if N <= 64 {
+            return bv(!self.bits() & Self::bit_mask());
+        }
+
+        for word in self.words.as_mut_slice() {
+            *word = !*word;
+        }
+        self.truncate()
     }
 }
 
-impl<const N: i128> std::ops::Add<i64> for BitVector<N> {
+impl<const N: i128> std::ops::Add<i64> for BitVector<N>
+where
+    Width<N>: SupportedWidth,
+{
     type Output = Self;
 
     fn add(self, rhs: i64) -> BitVector<N> {
-        let result = self.bits as i64 + rhs;
-        // If the result is out of bounds, we may want to handle overflow
-        bv::<N>(result as u64) // Returning the result as BitVector
+        if N <= 64 {
+            // Overflows wrap around
+            return bv::<N>(self.bits().wrapping_add(rhs as u64));
+        }
+
+        // Sign-extend the right hand side to the full width
+        let extension = if rhs < 0 { u64::MAX } else { 0 };
+        let mut rhs_words = [extension; MAX_WORDS];
+        rhs_words[0] = rhs as u64;
+        Self::from_array(add(self.to_array(), rhs_words))
     }
 }
 
 // ———————————————————————————————— Helpers ————————————————————————————————— //
 
 const fn mask(nb_ones: usize) -> u64 {
-    if nb_ones == 64 {
+    if nb_ones >= 64 {
         u64::MAX
     } else {
         (1 << nb_ones) - 1
     }
 }
 
+const fn u128_mask(nb_ones: usize) -> u128 {
+    if nb_ones >= 128 {
+        u128::MAX
+    } else {
+        (1 << nb_ones) - 1
+    }
+}
+
+/// Returns words with the first `nb_ones` bits set to 1.
+const fn ones(nb_ones: usize) -> [u64; MAX_WORDS] {
+    let mut words = [0; MAX_WORDS];
+    let mut i = 0;
+    while i < MAX_WORDS && i * 64 < nb_ones {
+        words[i] = mask(nb_ones - i * 64);
+        i += 1;
+    }
+    words
+}
+
+const fn shift_left(words: [u64; MAX_WORDS], shift: usize) -> [u64; MAX_WORDS] {
+    let mut result = [0; MAX_WORDS];
+    let (word_shift, bit_shift) = (shift / 64, shift % 64);
+    let mut i = word_shift;
+    while i < MAX_WORDS {
+        result[i] = words[i - word_shift] << bit_shift;
+        if bit_shift != 0 && i > word_shift {
+            result[i] |= words[i - word_shift - 1] >> (64 - bit_shift);
+        }
+        i += 1;
+    }
+    result
+}
+
+const fn shift_right(words: [u64; MAX_WORDS], shift: usize) -> [u64; MAX_WORDS] {
+    let mut result = [0; MAX_WORDS];
+    let (word_shift, bit_shift) = (shift / 64, shift % 64);
+    let mut i = 0;
+    while i + word_shift < MAX_WORDS {
+        result[i] = words[i + word_shift] >> bit_shift;
+        if bit_shift != 0 && i + word_shift + 1 < MAX_WORDS {
+            result[i] |= words[i + word_shift + 1] << (64 - bit_shift);
+        }
+        i += 1;
+    }
+    result
+}
+
+const fn add(a: [u64; MAX_WORDS], b: [u64; MAX_WORDS]) -> [u64; MAX_WORDS] {
+    let mut result = [0; MAX_WORDS];
+    let mut carry = false;
+    let mut i = 0;
+    while i < MAX_WORDS {
+        let (sum, carry_a) = a[i].overflowing_add(b[i]);
+        let (sum, carry_b) = sum.overflowing_add(carry as u64);
+        result[i] = sum;
+        carry = carry_a || carry_b;
+        i += 1;
+    }
+    result
+}
+
 // ————————————————————————————————— Tests —————————————————————————————————— //
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn hex_bits() {
+        assert_eq!(hex_bits_12_forwards(bv(0x7ff)), "0x7ff");
+        assert_eq!(hex_bits_forwards(64, bv::<64>(0)), "0x0");
+        assert_eq!(hex_bits_12_backwards(String::from("0x7ff")), bv(0x7ff));
+        assert_eq!(hex_bits_12_backwards(String::from("0x00fff")), bv(0xfff));
+        assert_eq!(
+            parse_hex_bits::<256>(256, String::from("0x10000000000000000")),
+            BitVector::<256>::new(1) << 64
+        );
+
+        // Malformed strings and values that do not fit return zero
+        for hex in ["0x1000", "0x", "fff", "0xfg", "-0x1", ""] {
+            assert_eq!(hex_bits_12_backwards(String::from(hex)), bv(0), "{hex}");
+        }
+        assert_eq!(parse_hex_bits::<12>(8, String::from("0x100")), bv(0));
+    }
+
     #[test]
     fn bitvec_masks() {
         assert_eq!(BitVector::<0>::bit_mask(), 0b0);
@@ -515,39 +987,39 @@ mod tests {
     #[test]
     fn test_update_subrange_bits() {
         assert_eq!(
-            update_subrange_bits(bv::<8>(0b11111100), 1, 0, bv::<2>(0b11)).bits,
+            update_subrange_bits(bv::<8>(0b11111100), 1, 0, bv::<2>(0b11)).bits(),
             0b11111111
         );
         assert_eq!(
-            update_subrange_bits(bv::<8>(0b00000000), 0, 0, bv::<1>(0b1)).bits,
+            update_subrange_bits(bv::<8>(0b00000000), 0, 0, bv::<1>(0b1)).bits(),
             0b00000001
         );
         assert_eq!(
-            update_subrange_bits(bv::<8>(0b00000000), 1, 1, bv::<1>(0b1)).bits,
+            update_subrange_bits(bv::<8>(0b00000000), 1, 1, bv::<1>(0b1)).bits(),
             0b00000010
         );
         assert_eq!(
-            update_subrange_bits(bv::<8>(0b00000000), 2, 2, bv::<1>(0b1)).bits,
+            update_subrange_bits(bv::<8>(0b00000000), 2, 2, bv::<1>(0b1)).bits(),
             0b00000100
         );
         assert_eq!(
-            update_subrange_bits(bv::<8>(0b00000000), 3, 3, bv::<1>(0b1)).bits,
+            update_subrange_bits(bv::<8>(0b00000000), 3, 3, bv::<1>(0b1)).bits(),
             0b00001000
         );
         assert_eq!(
-            update_subrange_bits(bv::<8>(0b00000000), 4, 4, bv::<1>(0b1)).bits,
+            update_subrange_bits(bv::<8>(0b00000000), 4, 4, bv::<1>(0b1)).bits(),
             0b00010000
         );
         assert_eq!(
-            update_subrange_bits(bv::<8>(0b00000000), 5, 5, bv::<1>(0b1)).bits,
+            update_subrange_bits(bv::<8>(0b00000000), 5, 5, bv::<1>(0b1)).bits(),
             0b00100000
         );
         assert_eq!(
-            update_subrange_bits(bv::<8>(0b00000000), 6, 6, bv::<1>(0b1)).bits,
+            update_subrange_bits(bv::<8>(0b00000000), 6, 6, bv::<1>(0b1)).bits(),
             0b01000000
         );
         assert_eq!(
-            update_subrange_bits(bv::<8>(0b00000000), 7, 7, bv::<1>(0b1)).bits,
+            update_subrange_bits(bv::<8>(0b00000000), 7, 7, bv::<1>(0b1)).bits(),
             0b10000000
         );
     }
@@ -575,9 +1047,9 @@ mod tests {
     fn test_zero_extend() {
         let v = bv::<8>(0b1010);
 
-        assert_eq!(v.bits, v.zero_extend::<16>().bits);
-        assert_eq!(v.bits, v.zero_extend::<63>().bits);
-        assert_eq!(v.bits, v.zero_extend::<64>().bits);
+        assert_eq!(v.bits(), v.zero_extend::<16>().bits());
+        assert_eq!(v.bits(), v.zero_extend::<63>().bits());
+        assert_eq!(v.bits(), v.zero_extend::<64>().bits());
     }
 
     #[test]
@@ -588,7 +1060,7 @@ mod tests {
         for i in 0..(1 << (SIZE as usize)) {
             let v = bv::<SIZE>(i);
             assert_eq!(
-                bitvector_concat::<SIZE, SIZE, NEW_SIZE>(v, v).bits,
+                bitvector_concat::<SIZE, SIZE, NEW_SIZE>(v, v).bits(),
                 i + (i << (SIZE as usize))
             );
         }
@@ -616,14 +1088,14 @@ mod tests {
             val |= 1u64 << idx;
             v = v.set_bit(idx as i128, true);
 
-            assert_eq!(v.bits, val);
+            assert_eq!(v.bits(), val);
         }
 
         for i in 0..(SIZE as usize) {
             v = v.set_bit(i as i128, false);
         }
 
-        assert_eq!(v.bits, 0);
+        assert_eq!(v.bits(), 0);
     }
 
     #[test]
@@ -709,4 +1181,91 @@ mod tests {
         assert_eq!(v.unsigned(), 0);
         assert_eq!(v.signed(), 0);
     }
+
+    #[test]
+    #[should_panic]
+    fn unsigned_overflow() {
+        sail_ones::<256>(256).unsigned();
+    }
+
+    #[test]
+    fn wide_bitvectors() {
+        let low = bv::<64>(0xffff_ffff_ffff_ffff);
+        let high = bv::<64>(0x1234);
+
+        // Concatenation and subranges across words
+        let v = bitvector_concat::<64, 64, 128>(high, low);
+        assert_eq!(v.bits_u128(), 0x1234_ffff_ffff_ffff_ffff);
+        assert_eq!(v.subrange::<64, 128, 64>(), high);
+        assert_eq!(v.subrange::<0, 64, 64>(), low);
+        assert_eq!(v.subrange::<60, 72, 12>().bits(), 0x34f);
+        assert_eq!(subrange_bits::<128, 8>(v, 71, 64).bits(), 0x34);
+
+        // Shifts across words
+        assert_eq!((v << 8usize).bits_u128(), 0x12_34ff_ffff_ffff_ffff_ff00);
+        assert_eq!((v << 64usize).bits_u128(), 0xffff_ffff_ffff_ffff << 64);
+        assert_eq!((v >> 60usize).bits_u128(), 0x1_234f);
+        assert_eq!((v << 128usize).bits_u128(), 0);
+
+        // Arithmetic carries between words
+        assert_eq!((v + 1).bits_u128(), 0x1235_0000_0000_0000_0000);
+        assert_eq!((bv::<128>(0) + -1).bits_u128(), u128::MAX);
+        assert_eq!(
+            v.wrapped_add(BitVector::from_u128(u128::MAX)).bits_u128(),
+            0x1234_ffff_ffff_ffff_fffe
+        );
+
+        // Bits above the width are always cleared
+        assert_eq!((!bv::<100>(0)).bits_u128(), (1 << 100) - 1);
+        assert_eq!(BitVector::<100>::from_u128(u128::MAX), !bv::<100>(0));
+        assert_eq!(sail_ones::<100>(100), !bv::<100>(0));
+
+        // Comparisons start from the most significant word
+        assert!(v > low.zero_extend::<128>());
+        assert!(bv::<128>(u64::MAX) < BitVector::from_u128(1 << 64));
+
+        // Updates
+        let v = bv::<256>(0).set_bit(200, true);
+        assert!(bitvector_access(v, 200));
+        assert_eq!(v.words()[3], 1 << 8);
+        let v = update_subrange_bits(v, 203, 196, bv::<8>(0xab));
+        assert_eq!(v.words()[3], 0xab << 4);
+        let v = v.set_subrange::<192, 256, 64>(bv(0x42));
+        assert_eq!(v.words()[3], 0x42);
+
+        // Signed interpretation
+        assert_eq!(BitVector::<128>::from_u128(u128::MAX).signed(), -1);
+        assert_eq!(BitVector::<100>::from_u128(1 << 99).signed(), -(1 << 99));
+        assert_eq!(get_slice_int::<128>(128, -1, 0).bits_u128(), u128::MAX);
+        assert_eq!(sail_ones::<256>(256).signed(), -1);
+        assert_eq!(bv::<256>(42).unsigned(), 42);
+        assert_eq!(bv::<512>(42).signed(), 42);
+
+        // Sign-extension beyond 64 bits
+        assert_eq!(bv::<8>(0x80).sign_extend::<256>(), !bv::<256>(0x7f));
+        assert_eq!(bv::<8>(0x7f).sign_extend::<256>(), bv::<256>(0x7f));
+        assert_eq!(
+            sign_extend::<12, 64>(64, bv(0x800)).bits(),
+            0xffff_ffff_ffff_f800
+        );
+        assert_eq!(
+            sign_extend::<100, 128>(128, BitVector::from_u128(1 << 99)).bits_u128(),
+            !((1 << 99) - 1)
+        );
+
+        // Words are sized to the width
+        assert_eq!(bv::<64>(1).words().len(), 1);
+        assert_eq!(bv::<65>(1).words().len(), 2);
+        assert_eq!(bv::<512>(1).words().len(), 8);
+        assert_eq!(BitVector::<65>::from_words(&[1, 3, 5]).words(), [1, 1]);
+        assert_eq!(std::mem::size_of::<BitVector<32>>(), 8);
+
+        // Formatting
+        assert_eq!(bits_str(bv::<0>(0)), "0");
+        assert_eq!(bits_str(v), format!("{:b}{}", 0x42, "0".repeat(192)));
+        assert_eq!(
+            format!("{:x}", bitvector_concat::<64, 64, 128>(high, bv(0x1))),
+            "12340000000000000001"
+        );
+    }
 }
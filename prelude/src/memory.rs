@@ -0,0 +1,504 @@
+//! Physical memory backends.
+//!
+//! The specifications do not define physical memory, instead they rely on the platform to provide
+//! it. This module defines the [Memory] trait used by the cores to access physical memory, as well
+//! as a default [SparseMemory] implementation.
+
+use crate::{BitVector, SupportedWidth, Width};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// An access fault raised by a memory backend.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct AccessFault {
+    /// The faulting physical address.
+    pub addr: u64,
+}
+
+/// A physical memory backend.
+///
+/// Accesses are at most 8 bytes wide, and values are little-endian. Accesses might be misaligned,
+/// it is up to the backend to decide whether to support them. Wider accesses issued by the core,
+/// such as `cbo.zero` zeroing a whole cache block, are split into naturally aligned accesses of
+/// 8 bytes.
+pub trait Memory: fmt::Debug + CloneMemory + Send {
+    /// Read `width` bytes at the physical address `addr`.
+    fn read(&mut self, addr: u64, width: usize) -> Result<u64, AccessFault>;
+
+    /// Write the `width` least significant bytes of `value` at the physical address `addr`.
+    fn write(&mut self, addr: u64, width: usize, value: u64) -> Result<(), AccessFault>;
+
+    /// Return true if the `width` bytes starting at `addr` are backed by this memory.
+    ///
+    /// The core raises an access fault on accesses to unmapped addresses before reaching the
+    /// backend. Accesses to mapped addresses are expected to succeed: reads rejected by
+    /// [Memory::read] return zero, and writes rejected by [Memory::write] are reported to the
+    /// specification as failed writes.
+    fn is_mapped(&self, addr: u64, width: usize) -> bool {
+        let _ = (addr, width);
+        true
+    }
+}
+
+/// Helper trait to clone boxed memory backends.
+///
+/// This trait is automatically implemented for all memory backends implementing [Clone].
+pub trait CloneMemory {
+    fn clone_memory(&self) -> Box<dyn Memory>;
+}
+
+impl<T: Memory + Clone + 'static> CloneMemory for T {
+    fn clone_memory(&self) -> Box<dyn Memory> {
+        Box::new(self.clone())
+    }
+}
+
+// ————————————————————————————— Sparse Memory —————————————————————————————— //
+
+pub(crate) const PAGE_SIZE: usize = 0x1000;
+const PAGE_MASK: u64 = PAGE_SIZE as u64 - 1;
+
+/// A sparse RAM covering the whole physical address space.
+///
+/// Pages are allocated on first write, reads from pages that have never been written return
+/// zero.
+#[derive(Clone, Debug, Default)]
+pub struct SparseMemory {
+    pub(crate) pages: BTreeMap<u64, Box<[u8; PAGE_SIZE]>>,
+}
+
+impl SparseMemory {
+    pub const fn new() -> Self {
+        SparseMemory {
+            pages: BTreeMap::new(),
+        }
+    }
+
+    fn read_byte(&self, addr: u64) -> u8 {
+        match self.pages.get(&(addr & !PAGE_MASK)) {
+            Some(page) => page[(addr & PAGE_MASK) as usize],
+            None => 0,
+        }
+    }
+
+    fn write_byte(&mut self, addr: u64, value: u8) {
+        let page = self
+            .pages
+            .entry(addr & !PAGE_MASK)
+            .or_insert_with(|| Box::new([0; PAGE_SIZE]));
+        page[(addr & PAGE_MASK) as usize] = value;
+    }
+}
+
+/// Pages that have never been written are equal to zeroed pages.
+impl PartialEq for SparseMemory {
+    fn eq(&self, other: &Self) -> bool {
+        let is_zero = |page: &[u8; PAGE_SIZE]| page.iter().all(|byte| *byte == 0);
+        let contains = |a: &Self, b: &Self| {
+            a.pages.iter().all(|(addr, page)| match b.pages.get(addr) {
+                Some(other) => page == other,
+                None => is_zero(page),
+            })
+        };
+        contains(self, other) && contains(other, self)
+    }
+}
+
+impl Eq for SparseMemory {}
+
+impl Memory for SparseMemory {
+    fn read(&mut self, addr: u64, width: usize) -> Result<u64, AccessFault> {
+        let mut value = 0;
+        for i in (0..width).rev() {
+            let byte = self.read_byte(addr.wrapping_add(i as u64));
+            value = (value << 8) | byte as u64;
+        }
+        Ok(value)
+    }
+
+    fn write(&mut self, addr: u64, width: usize, value: u64) -> Result<(), AccessFault> {
+        for i in 0..width {
+            let byte = (value >> (8 * i)) as u8;
+            self.write_byte(addr.wrapping_add(i as u64), byte);
+        }
+        Ok(())
+    }
+}
+
+/// A memory access performed by the core, as recorded by [PhysicalMemory::record_accesses].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemoryAccess {
+    /// The physical address of the access.
+    pub addr: u64,
+    /// The width of the access, in bytes.
+    pub width: usize,
+    /// The value read or written.
+    pub value: u64,
+    /// Whether the access is a write.
+    pub is_write: bool,
+}
+
+// ———————————————————————————— Physical Memory ————————————————————————————— //
+
+/// The physical memory attached to a core.
+///
+/// By default the physical memory is a [SparseMemory], but any [Memory] backend can be used
+/// instead.
+#[derive(Debug)]
+pub struct PhysicalMemory {
+    pub(crate) backend: Backend,
+    accesses: Option<Vec<MemoryAccess>>,
+}
+
+#[derive(Debug)]
+pub(crate) enum Backend {
+    Sparse(SparseMemory),
+    Custom(Box<dyn Memory>),
+}
+
+impl PhysicalMemory {
+    /// Creates a physical memory backed by a fresh [SparseMemory].
+    pub const fn new() -> Self {
+        PhysicalMemory {
+            backend: Backend::Sparse(SparseMemory::new()),
+            accesses: None,
+        }
+    }
+
+    /// Creates a physical memory backed by the provided memory backend.
+    pub fn with_backend(memory: impl Memory + 'static) -> Self {
+        PhysicalMemory {
+            backend: Backend::Custom(Box::new(memory)),
+            accesses: None,
+        }
+    }
+
+    /// Returns the memory backend.
+    pub fn backend(&self) -> &dyn Memory {
+        match &self.backend {
+            Backend::Sparse(memory) => memory,
+            Backend::Custom(memory) => memory.as_ref(),
+        }
+    }
+
+    /// Returns the memory backend.
+    pub fn backend_mut(&mut self) -> &mut dyn Memory {
+        match &mut self.backend {
+            Backend::Sparse(memory) => memory,
+            Backend::Custom(memory) => memory.as_mut(),
+        }
+    }
+
+    /// Start or stop recording the memory accesses performed by the core.
+    ///
+    /// Only the accesses issued by the specification are recorded, not those performed through
+    /// [PhysicalMemory::read] and [PhysicalMemory::write].
+    pub fn record_accesses(&mut self, enabled: bool) {
+        self.accesses = if enabled { Some(Vec::new()) } else { None };
+    }
+
+    /// Returns the memory accesses recorded since the last call.
+    pub fn take_accesses(&mut self) -> Vec<MemoryAccess> {
+        self.accesses
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// Read `width` bytes at the physical address `addr`.
+    pub fn read(&mut self, addr: u64, width: usize) -> Result<u64, AccessFault> {
+        self.backend_mut().read(addr, width)
+    }
+
+    /// Write the `width` least significant bytes of `value` at the physical address `addr`.
+    pub fn write(&mut self, addr: u64, width: usize, value: u64) -> Result<(), AccessFault> {
+        self.backend_mut().write(addr, width, value)
+    }
+
+    /// Copy a slice of bytes to memory, starting at the physical address `addr`.
+    pub fn write_bytes(&mut self, addr: u64, bytes: &[u8]) -> Result<(), AccessFault> {
+        for (offset, byte) in bytes.iter().enumerate() {
+            self.write(addr.wrapping_add(offset as u64), 1, *byte as u64)?;
+        }
+        Ok(())
+    }
+
+    /// Perform a data access issued by the specification on the little-endian `bytes`, and record
+    /// it if requested.
+    ///
+    /// Accesses wider than 8 bytes are split into naturally aligned accesses of at most 8 bytes.
+    fn access(&mut self, addr: u64, bytes: &mut [u8], is_write: bool) -> Result<(), AccessFault> {
+        let mut offset = 0;
+        while offset < bytes.len() {
+            let addr = addr.wrapping_add(offset as u64);
+            let width = match bytes.len() {
+                width @ ..=8 => width,
+                width => [8, 4, 2, 1]
+                    .into_iter()
+                    .find(|size| addr.is_multiple_of(*size as u64) && offset + size <= width)
+                    .unwrap(),
+            };
+            let chunk = &mut bytes[offset..offset + width];
+            let value = if is_write {
+                let mut buf = [0; 8];
+                buf[..width].copy_from_slice(chunk);
+                let value = u64::from_le_bytes(buf);
+                self.write(addr, width, value)?;
+                value
+            } else {
+                let value = self.read(addr, width)?;
+                chunk.copy_from_slice(&value.to_le_bytes()[..width]);
+                value
+            };
+            if let Some(accesses) = &mut self.accesses {
+                accesses.push(MemoryAccess {
+                    addr,
+                    width,
+                    value,
+                    is_write,
+                });
+            }
+            offset += width;
+        }
+        Ok(())
+    }
+
+    // The following functions are called from the translated specifications.
+    //
+    // The specifications check that each physical access is mapped before performing it, which is
+    // the only point at which an access fault can be raised.
+
+    pub fn is_mapped<const A: i128>(&self, addr: BitVector<A>, width: i128) -> bool
+    where
+        Width<A>: SupportedWidth,
+    {
+        self.backend().is_mapped(addr.bits(), width as usize)
+    }
+
+    pub fn read_mem<const A: i128, const N: i128>(
+        &mut self,
+        addr: BitVector<A>,
+        width: i128,
+    ) -> BitVector<N>
+    where
+        Width<A>: SupportedWidth,
+        Width<N>: SupportedWidth,
+    {
+        let mut bytes = vec![0; width as usize];
+        // Reads can not fail in the specification, mapped addresses are expected to be readable.
+        let _ = self.access(addr.bits(), &mut bytes, false);
+        let words: Vec<u64> = bytes
+            .chunks(8)
+            .map(|chunk| {
+                let mut buf = [0; 8];
+                buf[..chunk.len()].copy_from_slice(chunk);
+                u64::from_le_bytes(buf)
+            })
+            .collect();
+        BitVector::from_words(&words)
+    }
+
+    /// Returns false if the backend rejects the write, which the specification reports as a
+    /// failed write.
+    pub fn write_mem<const A: i128, const N: i128>(
+        &mut self,
+        addr: BitVector<A>,
+        width: i128,
+        data: BitVector<N>,
+    ) -> bool
+    where
+        Width<A>: SupportedWidth,
+        Width<N>: SupportedWidth,
+    {
+        let mut bytes: Vec<u8> = data
+            .words()
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .take(width as usize)
+            .collect();
+        self.access(addr.bits(), &mut bytes, true).is_ok()
+    }
+}
+
+impl Default for PhysicalMemory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clone for PhysicalMemory {
+    fn clone(&self) -> Self {
+        let backend = match &self.backend {
+            Backend::Sparse(memory) => Backend::Sparse(memory.clone()),
+            Backend::Custom(memory) => Backend::Custom(memory.clone_memory()),
+        };
+        PhysicalMemory {
+            backend,
+            accesses: self.accesses.clone(),
+        }
+    }
+}
+
+/// Memories are equal if their content is equal.
+///
+/// Custom backends can not be compared: a memory with a custom backend is never equal to one
+/// backed by a [SparseMemory], and custom backends are assumed to be equal to each other.
+impl PartialEq for PhysicalMemory {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.backend, &other.backend) {
+            (Backend::Sparse(a), Backend::Sparse(b)) => a == b,
+            (Backend::Custom(_), Backend::Custom(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for PhysicalMemory {}
+
+// ————————————————————————————————— Tests —————————————————————————————————— //
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bv;
+
+    #[test]
+    fn sparse_memory() {
+        let mut mem = SparseMemory::new();
+
+        assert_eq!(
+            mem.read(0x8000_0000, 8),
+            Ok(0),
+            "Fresh memory reads as zero"
+        );
+
+        mem.write(0x8000_0000, 4, 0xdead_beef).unwrap();
+        assert_eq!(mem.read(0x8000_0000, 4), Ok(0xdead_beef));
+        assert_eq!(
+            mem.read(0x8000_0000, 1),
+            Ok(0xef),
+            "Memory is little-endian"
+        );
+        assert_eq!(mem.read(0x8000_0002, 2), Ok(0xdead));
+        assert_eq!(mem.read(0x8000_0000, 8), Ok(0xdead_beef));
+
+        // Accesses can cross page boundaries
+        mem.write(0x8000_0ffc, 8, 0x0123_4567_89ab_cdef).unwrap();
+        assert_eq!(mem.read(0x8000_0ffc, 8), Ok(0x0123_4567_89ab_cdef));
+        assert_eq!(mem.read(0x8000_1000, 4), Ok(0x0123_4567));
+    }
+
+    #[derive(Clone, Debug)]
+    struct Rom;
+
+    impl Memory for Rom {
+        fn read(&mut self, _addr: u64, _width: usize) -> Result<u64, AccessFault> {
+            Ok(0x42)
+        }
+
+        fn write(&mut self, addr: u64, _width: usize, _value: u64) -> Result<(), AccessFault> {
+            Err(AccessFault { addr })
+        }
+
+        fn is_mapped(&self, addr: u64, width: usize) -> bool {
+            addr + width as u64 <= 0x1000
+        }
+    }
+
+    #[test]
+    fn custom_memory() {
+        let mut mem = PhysicalMemory::with_backend(Rom);
+
+        assert!(mem.is_mapped(bv::<64>(0x0), 8));
+        assert!(!mem.is_mapped(bv::<64>(0xffc), 8));
+        assert_eq!(mem.read_mem::<64, 8>(bv(0x10), 1).bits(), 0x42);
+        assert!(!mem.write_mem(bv::<64>(0x10), 1, bv::<8>(0x0)));
+
+        // Cloning preserves the custom backend
+        let mut clone = mem.clone();
+        assert_eq!(clone.read(0x10, 1), Ok(0x42));
+        assert_eq!(clone.write(0x10, 1, 0), Err(AccessFault { addr: 0x10 }));
+    }
+
+    #[test]
+    fn wide_accesses() {
+        let mut mem = PhysicalMemory::new();
+        mem.record_accesses(true);
+
+        // A 64 bytes cache block, as zeroed by `cbo.zero`
+        mem.write(0x1038, 8, u64::MAX).unwrap();
+        let block = BitVector::<512>::from_words(&[0x0123_4567_89ab_cdef; 8]);
+        assert!(mem.write_mem(bv::<64>(0x1000), 64, block));
+        assert_eq!(mem.read_mem::<64, 512>(bv(0x1000), 64), block);
+        assert!(mem.write_mem(bv::<64>(0x1000), 64, BitVector::<512>::new(0)));
+        assert_eq!(mem.read(0x1038, 8), Ok(0));
+        let accesses = mem.take_accesses();
+        assert_eq!(accesses.len(), 24, "Split into 8 bytes accesses");
+        assert!(accesses.iter().all(|access| access.width == 8));
+
+        // Misaligned wide accesses are split into naturally aligned accesses
+        assert!(mem.write_mem(bv::<64>(0x2006), 16, BitVector::<128>::from_u128(u128::MAX)));
+        let widths: Vec<_> = mem
+            .take_accesses()
+            .iter()
+            .map(|access| (access.addr, access.width))
+            .collect();
+        assert_eq!(widths, [(0x2006, 2), (0x2008, 8), (0x2010, 4), (0x2014, 2)]);
+        assert_eq!(mem.read(0x2004, 2), Ok(0));
+        assert_eq!(mem.read(0x2006, 8), Ok(u64::MAX));
+        assert_eq!(mem.read(0x2016, 2), Ok(0));
+    }
+
+    #[test]
+    fn memory_equality() {
+        let mut a = PhysicalMemory::new();
+        let mut b = PhysicalMemory::new();
+        a.write(0x1000, 8, 0).unwrap();
+        assert_eq!(a, b, "Zeroed pages are equal to unallocated pages");
+
+        a.write(0x1000, 8, 0x42).unwrap();
+        assert_ne!(a, b);
+        b.write(0x1000, 8, 0x42).unwrap();
+        assert_eq!(a, b);
+
+        assert_ne!(a, PhysicalMemory::with_backend(Rom));
+    }
+
+    #[test]
+    fn access_recording() {
+        let mut mem = PhysicalMemory::new();
+        mem.write_mem(bv::<64>(0x1000), 4, bv::<32>(0xdead_beef));
+        assert_eq!(
+            mem.take_accesses(),
+            [],
+            "Accesses are not recorded by default"
+        );
+
+        mem.record_accesses(true);
+        assert!(
+            mem.is_mapped(bv::<64>(0x1000), 4),
+            "Checks do not access memory"
+        );
+        mem.write_mem(bv::<64>(0x1000), 2, bv::<16>(0xcafe));
+        mem.read_mem::<64, 32>(bv(0x1000), 4);
+        mem.write(0x2000, 8, 0).unwrap();
+        assert_eq!(
+            mem.take_accesses(),
+            [
+                MemoryAccess {
+                    addr: 0x1000,
+                    width: 2,
+                    value: 0xcafe,
+                    is_write: true
+                },
+                MemoryAccess {
+                    addr: 0x1000,
+                    width: 4,
+                    value: 0xdead_cafe,
+                    is_write: false
+                },
+            ]
+        );
+        assert_eq!(mem.take_accesses(), []);
+    }
+}
@@ -0,0 +1,296 @@
+//! Serde support.
+//!
+//! Bitvectors are serialized as hexadecimal strings (e.g. `"0x80000000"`) in human-readable
+//! formats such as JSON, and as a sequence of little-endian 64 bits words in binary formats. The
+//! physical memory is serialized with its content, pages that have never been written are
+//! omitted.
+
+use crate::memory::{Backend, PAGE_SIZE};
+use crate::{
+    BitField, BitVector, MAX_BITVECTOR_WIDTH, PhysicalMemory, SparseMemory, SupportedWidth, Width,
+};
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{self, Serialize, SerializeStruct, Serializer};
+use std::collections::BTreeMap;
+
+impl<const N: i128> Serialize for BitVector<N>
+where
+    Width<N>: SupportedWidth,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(&format_args!("{:#x}", self))
+        } else {
+            serializer.collect_seq(self.words())
+        }
+    }
+}
+
+impl<'de, const N: i128> Deserialize<'de> for BitVector<N>
+where
+    Width<N>: SupportedWidth,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let expected = format!("a bitvector of {N} bits");
+        if deserializer.is_human_readable() {
+            let value = String::deserialize(deserializer)?;
+            parse_hex(&value).ok_or_else(|| {
+                de::Error::invalid_value(de::Unexpected::Str(&value), &expected.as_str())
+            })
+        } else {
+            let words = Vec::<u64>::deserialize(deserializer)?;
+            if words.len() != BitVector::<N>::new_empty().words().len() {
+                return Err(de::Error::invalid_length(words.len(), &expected.as_str()));
+            }
+            from_words_checked(&words)
+                .ok_or_else(|| de::Error::invalid_value(de::Unexpected::Seq, &expected.as_str()))
+        }
+    }
+}
+
+impl<const N: i128> Serialize for BitField<N>
+where
+    Width<N>: SupportedWidth,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.bits.serialize(serializer)
+    }
+}
+
+impl<'de, const N: i128> Deserialize<'de> for BitField<N>
+where
+    Width<N>: SupportedWidth,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(BitField {
+            bits: BitVector::deserialize(deserializer)?,
+        })
+    }
+}
+
+/// Only memories backed by a [SparseMemory] can be serialized, custom backends return an error.
+///
+/// The accesses being recorded are not serialized.
+impl Serialize for PhysicalMemory {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let Backend::Sparse(memory) = &self.backend else {
+            return Err(ser::Error::custom(
+                "memories with a custom backend can not be serialized",
+            ));
+        };
+        let pages = memory
+            .pages
+            .iter()
+            .filter(|(_, page)| page.iter().any(|byte| *byte != 0))
+            .map(|(addr, page)| (addr, PageRef(page)));
+        let mut state = serializer.serialize_struct("PhysicalMemory", 1)?;
+        state.serialize_field("pages", &Pages(pages))?;
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for PhysicalMemory {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct Repr {
+            pages: BTreeMap<u64, Page>,
+        }
+
+        let repr = Repr::deserialize(deserializer)?;
+        let mut memory = SparseMemory::new();
+        for (addr, Page(page)) in repr.pages {
+            if addr % PAGE_SIZE as u64 != 0 {
+                return Err(de::Error::custom(format!(
+                    "unaligned page address {addr:#x}"
+                )));
+            }
+            memory.pages.insert(addr, page);
+        }
+        let mut mem = PhysicalMemory::new();
+        mem.backend = Backend::Sparse(memory);
+        Ok(mem)
+    }
+}
+
+/// The pages of a [SparseMemory], indexed by address.
+struct Pages<I>(I);
+
+impl<'a, I> Serialize for Pages<I>
+where
+    I: Iterator<Item = (&'a u64, PageRef<'a>)> + Clone,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.clone())
+    }
+}
+
+/// Pages are serialized as hexadecimal strings in human-readable formats, and as a sequence of
+/// bytes in binary formats.
+struct PageRef<'a>(&'a [u8; PAGE_SIZE]);
+
+impl Serialize for PageRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            let hex: String = self.0.iter().map(|byte| format!("{byte:02x}")).collect();
+            serializer.serialize_str(&hex)
+        } else {
+            serializer.collect_seq(self.0)
+        }
+    }
+}
+
+struct Page(Box<[u8; PAGE_SIZE]>);
+
+impl<'de> Deserialize<'de> for Page {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let expected = format!("a page of {PAGE_SIZE} bytes");
+        let bytes = if deserializer.is_human_readable() {
+            let hex = String::deserialize(deserializer)?;
+            let invalid =
+                || de::Error::invalid_value(de::Unexpected::Str(&hex), &expected.as_str());
+            if !hex.is_ascii() || hex.len() % 2 != 0 {
+                return Err(invalid());
+            }
+            (0..hex.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+                .collect::<Result<Vec<u8>, _>>()
+                .map_err(|_| invalid())?
+        } else {
+            Vec::<u8>::deserialize(deserializer)?
+        };
+        let len = bytes.len();
+        let page = bytes.into_boxed_slice().try_into();
+        page.map(Page)
+            .map_err(|_| de::Error::invalid_length(len, &expected.as_str()))
+    }
+}
+
+/// Serialize and deserialize arrays of any length.
+///
+/// Serde only implements its traits for arrays of up to 32 elements, the generated code uses this
+/// module for array fields with `#[serde(with = "softcore_prelude::serde_array")]`.
+pub mod serde_array {
+    use serde::de::{self, Deserialize, Deserializer};
+    use serde::ser::{Serialize, Serializer};
+
+    pub fn serialize<S, T, const N: usize>(array: &[T; N], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Serialize,
+    {
+        serializer.collect_seq(array)
+    }
+
+    pub fn deserialize<'de, D, T, const N: usize>(deserializer: D) -> Result<[T; N], D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        let items = Vec::<T>::deserialize(deserializer)?;
+        let len = items.len();
+        items.try_into().map_err(|_| {
+            de::Error::invalid_length(len, &format!("an array of {N} elements").as_str())
+        })
+    }
+}
+
+// ———————————————————————————————— Helpers ————————————————————————————————— //
+
+/// Parse an hexadecimal string prefixed with `0x`, returns `None` if the value does not fit.
+fn parse_hex<const N: i128>(value: &str) -> Option<BitVector<N>>
+where
+    Width<N>: SupportedWidth,
+{
+    let digits = value.strip_prefix("0x")?;
+    if digits.is_empty() || digits.len() > MAX_BITVECTOR_WIDTH as usize / 4 {
+        return None;
+    }
+
+    let mut words = vec![0; digits.len().div_ceil(16)];
+    for (i, digit) in digits.chars().rev().enumerate() {
+        words[i / 16] |= (digit.to_digit(16)? as u64) << (4 * (i % 16));
+    }
+    from_words_checked(&words)
+}
+
+/// Returns `None` if the words have bits set above N.
+fn from_words_checked<const N: i128>(words: &[u64]) -> Option<BitVector<N>>
+where
+    Width<N>: SupportedWidth,
+{
+    let bitvector = BitVector::from_words(words);
+    let truncated = bitvector.words().iter().chain(std::iter::repeat(&0));
+    words
+        .iter()
+        .zip(truncated)
+        .all(|(word, truncated)| word == truncated)
+        .then_some(bitvector)
+}
+
+// ————————————————————————————————— Tests —————————————————————————————————— //
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bv;
+
+    #[test]
+    fn bitvector_json() {
+        let value = bv::<32>(0xdead_beef);
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(json, "\"0xdeadbeef\"");
+        assert_eq!(serde_json::from_str::<BitVector<32>>(&json).unwrap(), value);
+
+        let wide = BitVector::<256>::new(0x42) << 200;
+        let json = serde_json::to_string(&wide).unwrap();
+        assert_eq!(serde_json::from_str::<BitVector<256>>(&json).unwrap(), wide);
+
+        // Values must fit in the bitvector
+        assert!(serde_json::from_str::<BitVector<8>>("\"0x100\"").is_err());
+        assert!(serde_json::from_str::<BitVector<8>>("\"0xfg\"").is_err());
+        assert!(serde_json::from_str::<BitVector<8>>("\"42\"").is_err());
+    }
+
+    #[test]
+    fn arrays_and_memory() {
+        #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+        struct Registers {
+            #[serde(with = "serde_array")]
+            regs: [BitVector<64>; 40],
+            field: BitField<8>,
+            mem: PhysicalMemory,
+        }
+
+        let mut mem = PhysicalMemory::new();
+        mem.write(0x1000, 8, 0x1234).unwrap();
+        mem.write(0x5000, 8, 0).unwrap();
+        let registers = Registers {
+            regs: [bv(0x10); 40],
+            field: BitField::new(0xff),
+            mem,
+        };
+        let json = serde_json::to_string(&registers).unwrap();
+        assert!(!json.contains("20480"), "zero pages are not serialized");
+        let mut restored: Registers = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, registers);
+        assert_eq!(restored.mem.read(0x1000, 8).unwrap(), 0x1234);
+
+        let json = json.replace("\"0x10\",", "");
+        assert!(serde_json::from_str::<Registers>(&json).is_err());
+
+        // Custom backends can not be serialized
+        #[derive(Clone, Debug)]
+        struct Rom;
+        impl crate::Memory for Rom {
+            fn read(&mut self, _: u64, _: usize) -> Result<u64, crate::AccessFault> {
+                Ok(0)
+            }
+            fn write(&mut self, addr: u64, _: usize, _: u64) -> Result<(), crate::AccessFault> {
+                Err(crate::AccessFault { addr })
+            }
+        }
+        assert!(serde_json::to_string(&PhysicalMemory::with_backend(Rom)).is_err());
+    }
+}
@@ -0,0 +1,157 @@
+//! Bitvector widths.
+//!
+//! The storage of a [BitVector](crate::BitVector) depends on its width: bitvectors of up to 64 bits
+//! are stored in a single `u64`, wider bitvectors in an array of `N.div_ceil(64)` words. Stable
+//! Rust can not compute the storage type from `N` directly, instead [SupportedWidth] maps each
+//! supported width to its storage. As a consequence, code generic over the width of bitvectors
+//! must carry a `Width<N>: SupportedWidth` bound.
+
+use crate::MAX_BITVECTOR_WIDTH;
+use std::fmt;
+
+/// The number of words backing the widest bitvectors.
+pub(crate) const MAX_WORDS: usize = MAX_BITVECTOR_WIDTH as usize / 64;
+
+/// The width of a bitvector, see [SupportedWidth].
+pub struct Width<const N: i128>;
+
+/// The widths supported by bitvectors, from 0 up to [MAX_BITVECTOR_WIDTH] bits.
+pub trait SupportedWidth: sealed::Sealed {
+    /// The words backing bitvectors of this width.
+    type Words: Words;
+}
+
+/// The little-endian 64 bits words backing a bitvector.
+pub trait Words: sealed::Sealed + Copy + Eq + Default + fmt::Debug + Send + Sync + 'static {
+    /// Returns the words, least significant first.
+    fn as_slice(&self) -> &[u64];
+
+    /// Returns the words, least significant first.
+    fn as_mut_slice(&mut self) -> &mut [u64];
+}
+
+impl sealed::Sealed for u64 {}
+
+impl Words for u64 {
+    fn as_slice(&self) -> &[u64] {
+        std::slice::from_ref(self)
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [u64] {
+        std::slice::from_mut(self)
+    }
+}
+
+macro_rules! word_arrays {
+    ($($len:literal)*) => {
+        $(
+            impl sealed::Sealed for [u64; $len] {}
+
+            impl Words for [u64; $len] {
+                fn as_slice(&self) -> &[u64] {
+                    self
+                }
+
+                fn as_mut_slice(&mut self) -> &mut [u64] {
+                    self
+                }
+            }
+        )*
+    };
+}
+
+word_arrays!(2 3 4 5 6 7 8);
+
+/// The words of a bitvector, overlapping the words of the widest bitvectors.
+///
+/// This is used to manipulate bitvectors of any width in const functions, where the methods of
+/// the [Words] trait can not be called.
+union Repr<W: Copy> {
+    words: W,
+    array: [u64; MAX_WORDS],
+}
+
+/// Returns the words zero-extended to the width of the widest bitvectors.
+pub(crate) const fn to_array<W: Words>(words: W) -> [u64; MAX_WORDS] {
+    let mut repr = Repr {
+        array: [0; MAX_WORDS],
+    };
+    repr.words = words;
+    // SAFETY: all the bytes of the array have been initialized, and the [Words] implementations
+    // are no larger than the array.
+    unsafe { repr.array }
+}
+
+/// Returns the first words of the array, the remaining words are discarded.
+pub(crate) const fn from_array<W: Words>(array: [u64; MAX_WORDS]) -> W {
+    let repr = Repr { array };
+    // SAFETY: the [Words] implementations are arrays of (or a single) u64 no larger than the
+    // array, for which all bit patterns are valid.
+    unsafe { repr.words }
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+macro_rules! supported_widths {
+    ($words:ty; $($width:literal)*) => {
+        $(
+            impl sealed::Sealed for Width<$width> {}
+
+            impl SupportedWidth for Width<$width> {
+                type Words = $words;
+            }
+        )*
+    };
+}
+
+supported_widths!(u64;
+    0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15
+    16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31
+    32 33 34 35 36 37 38 39 40 41 42 43 44 45 46 47
+    48 49 50 51 52 53 54 55 56 57 58 59 60 61 62 63
+    64
+);
+supported_widths!([u64; 2];
+    65 66 67 68 69 70 71 72 73 74 75 76 77 78 79 80
+    81 82 83 84 85 86 87 88 89 90 91 92 93 94 95 96
+    97 98 99 100 101 102 103 104 105 106 107 108 109 110 111 112
+    113 114 115 116 117 118 119 120 121 122 123 124 125 126 127 128
+);
+supported_widths!([u64; 3];
+    129 130 131 132 133 134 135 136 137 138 139 140 141 142 143 144
+    145 146 147 148 149 150 151 152 153 154 155 156 157 158 159 160
+    161 162 163 164 165 166 167 168 169 170 171 172 173 174 175 176
+    177 178 179 180 181 182 183 184 185 186 187 188 189 190 191 192
+);
+supported_widths!([u64; 4];
+    193 194 195 196 197 198 199 200 201 202 203 204 205 206 207 208
+    209 210 211 212 213 214 215 216 217 218 219 220 221 222 223 224
+    225 226 227 228 229 230 231 232 233 234 235 236 237 238 239 240
+    241 242 243 244 245 246 247 248 249 250 251 252 253 254 255 256
+);
+supported_widths!([u64; 5];
+    257 258 259 260 261 262 263 264 265 266 267 268 269 270 271 272
+    273 274 275 276 277 278 279 280 281 282 283 284 285 286 287 288
+    289 290 291 292 293 294 295 296 297 298 299 300 301 302 303 304
+    305 306 307 308 309 310 311 312 313 314 315 316 317 318 319 320
+);
+supported_widths!([u64; 6];
+    321 322 323 324 325 326 327 328 329 330 331 332 333 334 335 336
+    337 338 339 340 341 342 343 344 345 346 347 348 349 350 351 352
+    353 354 355 356 357 358 359 360 361 362 363 364 365 366 367 368
+    369 370 371 372 373 374 375 376 377 378 379 380 381 382 383 384
+);
+supported_widths!([u64; 7];
+    385 386 387 388 389 390 391 392 393 394 395 396 397 398 399 400
+    401 402 403 404 405 406 407 408 409 410 411 412 413 414 415 416
+    417 418 419 420 421 422 423 424 425 426 427 428 429 430 431 432
+    433 434 435 436 437 438 439 440 441 442 443 444 445 446 447 448
+);
+supported_widths!([u64; 8];
+    449 450 451 452 453 454 455 456 457 458 459 460 461 462 463 464
+    465 466 467 468 469 470 471 472 473 474 475 476 477 478 479 480
+    481 482 483 484 485 486 487 488 489 490 491 492 493 494 495 496
+    497 498 499 500 501 502 503 504 505 506 507 508 509 510 511 512
+);
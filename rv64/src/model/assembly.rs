@@ -0,0 +1,34 @@
+//! Assembly
+//!
+//! Instructions are displayed with the `assembly` mapping of the specification, which prints
+//! registers with their ABI names and immediates in hexadecimal (e.g. `csrrw zero, mstatus, zero`).
+
+use super::raw::{self, Core, ast};
+use super::{config, new_core};
+use std::cell::RefCell;
+use std::fmt;
+
+impl Core {
+    /// Returns the assembly of an instruction, using the `assembly` mapping of the specification.
+    ///
+    /// Register names depend on the configuration of the core, for instance floating point
+    /// operands are general purpose registers on cores implementing `Zfinx`.
+    pub fn assembly(&mut self, instr: ast) -> String {
+        raw::assembly_forwards(self, instr)
+    }
+}
+
+/// Instructions are displayed as on a core implementing [config::RVA23U64], see
+/// [Core::assembly] to display instructions for a given core.
+///
+/// The `assembly` mapping is not pure: it reads the configuration of the core (e.g. to name the
+/// operands of `Zfinx` instructions). Each thread displaying instructions thus allocates an
+/// [config::RVA23U64] core on first use, kept until the thread exits.
+impl fmt::Display for ast {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        thread_local! {
+            static CORE: RefCell<Box<Core>> = RefCell::new(Box::new(new_core(config::RVA23U64)));
+        }
+        CORE.with_borrow_mut(|core| f.write_str(&core.assembly(*self)))
+    }
+}
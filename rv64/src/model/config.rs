@@ -0,0 +1,820 @@
+//! A set of pre-defined core configurations.
+//!
+//! The configuration defines which RISC-V extensions are available, as well as some other
+//! parameters such as the number and granularity of PMP registers implemented by the core.
+//!
+//! PRs to contribute new configurations for popular cores are welcome.
+
+use super::{VLEN_EXP, raw};
+use softcore_prelude::BitVector;
+use std::fmt;
+
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "json")]
+pub use json::{JsonConfig, from_json};
+
+/// A configuration with all extensions disabled.
+pub const MINIMAL: raw::Config = raw::Config {
+    extensions: raw::ConfigExtensions {
+        A: raw::ConfigA { supported: false },
+        B: raw::ConfigB { supported: false },
+        FD: raw::ConfigFD { supported: false },
+        M: raw::ConfigM { supported: false },
+        S: raw::ConfigS { supported: false },
+        Smcntrpmf: raw::ConfigSmcntrpmf { supported: false },
+        Sscofpmf: raw::ConfigSscofpmf { supported: false },
+        Sstc: raw::ConfigSstc { supported: false },
+        Sv32: raw::ConfigSv32 { supported: false },
+        Sv39: raw::ConfigSv39 { supported: false },
+        Sv48: raw::ConfigSv48 { supported: false },
+        Sv57: raw::ConfigSv57 { supported: false },
+        Svbare: raw::ConfigSvbare { supported: false },
+        Svinval: raw::ConfigSvinval { supported: false },
+        U: raw::ConfigU { supported: false },
+        V: raw::ConfigV {
+            supported: false,
+            vlen_exp: VLEN_EXP,
+        },
+        Zaamo: raw::ConfigZaamo { supported: false },
+        Zabha: raw::ConfigZabha { supported: false },
+        Zalrsc: raw::ConfigZalrsc { supported: false },
+        Zba: raw::ConfigZba { supported: false },
+        Zbb: raw::ConfigZbb { supported: false },
+        Zbc: raw::ConfigZbc { supported: false },
+        Zbkb: raw::ConfigZbkb { supported: false },
+        Zbkc: raw::ConfigZbkc { supported: false },
+        Zbkx: raw::ConfigZbkx { supported: false },
+        Zbs: raw::ConfigZbs { supported: false },
+        Zca: raw::ConfigZca { supported: false },
+        Zcb: raw::ConfigZcb { supported: false },
+        Zcd: raw::ConfigZcd { supported: false },
+        Zcf: raw::ConfigZcf { supported: false },
+        Zcmop: raw::ConfigZcmop { supported: false },
+        Zfa: raw::ConfigZfa { supported: false },
+        Zfh: raw::ConfigZfh { supported: false },
+        Zfhmin: raw::ConfigZfhmin { supported: false },
+        Zfinx: raw::ConfigZfinx { supported: false },
+        Zhinx: raw::ConfigZhinx { supported: false },
+        Zicbom: raw::ConfigZicbom { supported: false },
+        Zicboz: raw::ConfigZicboz { supported: false },
+        Zicntr: raw::ConfigZicntr { supported: false },
+        Zicond: raw::ConfigZicond { supported: false },
+        Zifencei: raw::ConfigZifencei { supported: false },
+        Zihpm: raw::ConfigZihpm { supported: false },
+        Zimop: raw::ConfigZimop { supported: false },
+        Zknd: raw::ConfigZknd { supported: false },
+        Zkne: raw::ConfigZkne { supported: false },
+        Zknh: raw::ConfigZknh { supported: false },
+        Zkr: raw::ConfigZkr { supported: false },
+        Zksed: raw::ConfigZksed { supported: false },
+        Zksh: raw::ConfigZksh { supported: false },
+        Zmmul: raw::ConfigZmmul { supported: false },
+        Zvbb: raw::ConfigZvbb { supported: false },
+        Zvbc: raw::ConfigZvbc { supported: false },
+        Zvkb: raw::ConfigZvkb { supported: false },
+        Zvknha: raw::ConfigZvknha { supported: false },
+        Zvknhb: raw::ConfigZvknhb { supported: false },
+        Zvksh: raw::ConfigZvksh { supported: false },
+    },
+    base: raw::ConfigBase {
+        writable_fiom: false,
+        writable_hpm_counters: BitVector::new(0),
+        writable_misa: false,
+    },
+    memory: raw::ConfigMemory {
+        pmp: raw::ConfigPmp { count: 0, grain: 0 },
+    },
+    platform: raw::ConfigPlatform {
+        archid: 0,
+        hartid: 0,
+        impid: 0,
+        vendorid: 0,
+    },
+};
+
+/// The SiFive U74 core configuration.
+pub const U74: raw::Config = raw::Config {
+    extensions: raw::ConfigExtensions {
+        A: raw::ConfigA { supported: true },
+        B: raw::ConfigB { supported: true },
+        FD: raw::ConfigFD { supported: true },
+        M: raw::ConfigM { supported: true },
+        S: raw::ConfigS { supported: true },
+        Smcntrpmf: raw::ConfigSmcntrpmf { supported: false },
+        Sscofpmf: raw::ConfigSscofpmf { supported: false },
+        Sstc: raw::ConfigSstc { supported: false },
+        Sv32: raw::ConfigSv32 { supported: false },
+        Sv39: raw::ConfigSv39 { supported: true },
+        Sv48: raw::ConfigSv48 { supported: false },
+        Sv57: raw::ConfigSv57 { supported: false },
+        Svbare: raw::ConfigSvbare { supported: false },
+        Svinval: raw::ConfigSvinval { supported: false },
+        U: raw::ConfigU { supported: true },
+        V: raw::ConfigV {
+            supported: false,
+            vlen_exp: VLEN_EXP,
+        },
+        Zaamo: raw::ConfigZaamo { supported: false },
+        Zabha: raw::ConfigZabha { supported: false },
+        Zalrsc: raw::ConfigZalrsc { supported: false },
+        Zba: raw::ConfigZba { supported: true },
+        Zbb: raw::ConfigZbb { supported: true },
+        Zbc: raw::ConfigZbc { supported: false },
+        Zbkb: raw::ConfigZbkb { supported: false },
+        Zbkc: raw::ConfigZbkc { supported: false },
+        Zbkx: raw::ConfigZbkx { supported: false },
+        Zbs: raw::ConfigZbs { supported: false },
+        Zca: raw::ConfigZca { supported: true },
+        Zcb: raw::ConfigZcb { supported: true },
+        Zcd: raw::ConfigZcd { supported: true },
+        Zcf: raw::ConfigZcf { supported: true },
+        Zcmop: raw::ConfigZcmop { supported: false },
+        Zfa: raw::ConfigZfa { supported: false },
+        Zfh: raw::ConfigZfh { supported: false },
+        Zfhmin: raw::ConfigZfhmin { supported: false },
+        Zfinx: raw::ConfigZfinx { supported: false },
+        Zhinx: raw::ConfigZhinx { supported: false },
+        Zicbom: raw::ConfigZicbom { supported: false },
+        Zicboz: raw::ConfigZicboz { supported: false },
+        Zicntr: raw::ConfigZicntr { supported: true },
+        Zicond: raw::ConfigZicond { supported: false },
+        Zifencei: raw::ConfigZifencei { supported: false },
+        Zihpm: raw::ConfigZihpm { supported: false },
+        Zimop: raw::ConfigZimop { supported: false },
+        Zknd: raw::ConfigZknd { supported: false },
+        Zkne: raw::ConfigZkne { supported: false },
+        Zknh: raw::ConfigZknh { supported: false },
+        Zkr: raw::ConfigZkr { supported: false },
+        Zksed: raw::ConfigZksed { supported: false },
+        Zksh: raw::ConfigZksh { supported: false },
+        Zmmul: raw::ConfigZmmul { supported: false },
+        Zvbb: raw::ConfigZvbb { supported: false },
+        Zvbc: raw::ConfigZvbc { supported: false },
+        Zvkb: raw::ConfigZvkb { supported: false },
+        Zvknha: raw::ConfigZvknha { supported: false },
+        Zvknhb: raw::ConfigZvknhb { supported: false },
+        Zvksh: raw::ConfigZvksh { supported: false },
+    },
+    base: raw::ConfigBase {
+        writable_fiom: true,
+        writable_hpm_counters: BitVector::new(0), // TODO: check on a board
+        writable_misa: false,
+    },
+    memory: raw::ConfigMemory {
+        pmp: raw::ConfigPmp {
+            count: 16,
+            grain: 10,
+        },
+    },
+    platform: raw::ConfigPlatform {
+        archid: 0,
+        hartid: 0,
+        impid: 0,
+        vendorid: 0,
+    },
+};
+
+/// A generic core implementing the RVA22U64 profile.
+///
+/// All the mandatory extensions of the RVA22U64 and RVA22S64 profiles supported by the model are
+/// enabled.
+pub const RVA22U64: raw::Config = raw::Config {
+    extensions: raw::ConfigExtensions {
+        A: raw::ConfigA { supported: true },
+        FD: raw::ConfigFD { supported: true },
+        M: raw::ConfigM { supported: true },
+        S: raw::ConfigS { supported: true },
+        Sv39: raw::ConfigSv39 { supported: true },
+        Svbare: raw::ConfigSvbare { supported: true },
+        Svinval: raw::ConfigSvinval { supported: true },
+        U: raw::ConfigU { supported: true },
+        Zba: raw::ConfigZba { supported: true },
+        Zbb: raw::ConfigZbb { supported: true },
+        Zbs: raw::ConfigZbs { supported: true },
+        Zca: raw::ConfigZca { supported: true },
+        Zcd: raw::ConfigZcd { supported: true },
+        Zfhmin: raw::ConfigZfhmin { supported: true },
+        Zicbom: raw::ConfigZicbom { supported: true },
+        Zicboz: raw::ConfigZicboz { supported: true },
+        Zicntr: raw::ConfigZicntr { supported: true },
+        Zifencei: raw::ConfigZifencei { supported: true },
+        Zihpm: raw::ConfigZihpm { supported: true },
+        ..MINIMAL.extensions
+    },
+    base: raw::ConfigBase {
+        writable_fiom: true,
+        writable_hpm_counters: BitVector::new(0xffff_fff8),
+        writable_misa: false,
+    },
+    memory: raw::ConfigMemory {
+        pmp: raw::ConfigPmp {
+            count: 16,
+            grain: 0,
+        },
+    },
+    platform: MINIMAL.platform,
+};
+
+/// A generic core implementing the RVA23U64 profile.
+///
+/// All the mandatory extensions of the RVA23U64 and RVA23S64 profiles supported by the model are
+/// enabled. Note that the vector length is fixed when compiling the model, the profile requires
+/// a VLEN of at least 128 bits which is provided by the `vlen128`, `vlen256` and `vlen512`
+/// variants.
+pub const RVA23U64: raw::Config = raw::Config {
+    extensions: raw::ConfigExtensions {
+        B: raw::ConfigB { supported: true },
+        Sscofpmf: raw::ConfigSscofpmf { supported: true },
+        Sstc: raw::ConfigSstc { supported: true },
+        V: raw::ConfigV {
+            supported: true,
+            vlen_exp: VLEN_EXP,
+        },
+        Zcb: raw::ConfigZcb { supported: true },
+        Zcmop: raw::ConfigZcmop { supported: true },
+        Zfa: raw::ConfigZfa { supported: true },
+        Zicond: raw::ConfigZicond { supported: true },
+        Zimop: raw::ConfigZimop { supported: true },
+        Zvbb: raw::ConfigZvbb { supported: true },
+        ..RVA22U64.extensions
+    },
+    ..RVA22U64
+};
+
+/// A configuration close to the T-Head C906 core, but with 16 PMP entries.
+///
+/// The C906 implements 8 PMP entries, but the specification only supports 0, 16 or 64 entries.
+/// Apart from the PMP, this configuration matches the C906 and C910 cores.
+pub const C906_PMP16: raw::Config = raw::Config {
+    extensions: raw::ConfigExtensions {
+        A: raw::ConfigA { supported: true },
+        FD: raw::ConfigFD { supported: true },
+        M: raw::ConfigM { supported: true },
+        S: raw::ConfigS { supported: true },
+        Sv39: raw::ConfigSv39 { supported: true },
+        Svbare: raw::ConfigSvbare { supported: true },
+        U: raw::ConfigU { supported: true },
+        Zca: raw::ConfigZca { supported: true },
+        Zcd: raw::ConfigZcd { supported: true },
+        Zicntr: raw::ConfigZicntr { supported: true },
+        Zifencei: raw::ConfigZifencei { supported: true },
+        ..MINIMAL.extensions
+    },
+    base: raw::ConfigBase {
+        writable_fiom: false,
+        writable_hpm_counters: BitVector::new(0),
+        writable_misa: false,
+    },
+    memory: raw::ConfigMemory {
+        pmp: raw::ConfigPmp {
+            count: 16,
+            grain: 10,
+        },
+    },
+    platform: raw::ConfigPlatform {
+        archid: 0,
+        hartid: 0,
+        impid: 0,
+        vendorid: 0x5b7,
+    },
+};
+
+/// A minimal RV64IM microcontroller, running in machine mode only.
+///
+/// Neither supervisor nor user mode are implemented, and the core has no PMP.
+pub const MCU: raw::Config = raw::Config {
+    extensions: raw::ConfigExtensions {
+        M: raw::ConfigM { supported: true },
+        ..MINIMAL.extensions
+    },
+    ..MINIMAL
+};
+
+// ————————————————————————————— Config Builder ————————————————————————————— //
+
+/// The extension flags, by name.
+macro_rules! extensions {
+    ($($name:ident),* $(,)?) => {
+        /// The names of all the extensions that can be enabled or disabled in a configuration.
+        pub const EXTENSIONS: &[&str] = &[$(stringify!($name)),*];
+
+        /// Returns whether an extension is enabled, or [None] if the extension is unknown.
+        pub fn extension_supported(config: &raw::Config, name: &str) -> Option<bool> {
+            match name {
+                $(stringify!($name) => Some(config.extensions.$name.supported),)*
+                _ => None,
+            }
+        }
+
+        fn extension_mut<'a>(config: &'a mut raw::Config, name: &str) -> Option<&'a mut bool> {
+            match name {
+                $(stringify!($name) => Some(&mut config.extensions.$name.supported),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+extensions!(
+    A, B, FD, M, S, Smcntrpmf, Sscofpmf, Sstc, Sv32, Sv39, Sv48, Sv57, Svbare, Svinval, U, V,
+    Zaamo, Zabha, Zalrsc, Zba, Zbb, Zbc, Zbkb, Zbkc, Zbkx, Zbs, Zca, Zcb, Zcd, Zcf, Zcmop, Zfa,
+    Zfh, Zfhmin, Zfinx, Zhinx, Zicbom, Zicboz, Zicntr, Zicond, Zifencei, Zihpm, Zimop, Zknd, Zkne,
+    Zknh, Zkr, Zksed, Zksh, Zmmul, Zvbb, Zvbc, Zvkb, Zvknha, Zvknhb, Zvksh,
+);
+
+/// Converts a configuration of the crate root model into a configuration of this model.
+///
+/// Each model variant has its own configuration type, with the same fields. This is used by
+/// [crate::new_any_core] to select the model variant at runtime.
+pub fn from_root(root: &crate::raw::Config) -> raw::Config {
+    let mut config = MINIMAL;
+    for name in EXTENSIONS {
+        *extension_mut(&mut config, name).unwrap() =
+            crate::config::extension_supported(root, name).unwrap();
+    }
+    config.extensions.V.vlen_exp = root.extensions.V.vlen_exp;
+    config.base = raw::ConfigBase {
+        writable_fiom: root.base.writable_fiom,
+        writable_hpm_counters: BitVector::new(root.base.writable_hpm_counters.bits()),
+        writable_misa: root.base.writable_misa,
+    };
+    config.memory.pmp = raw::ConfigPmp {
+        count: root.memory.pmp.count,
+        grain: root.memory.pmp.grain,
+    };
+    config.platform = raw::ConfigPlatform {
+        archid: root.platform.archid,
+        hartid: root.platform.hartid,
+        impid: root.platform.impid,
+        vendorid: root.platform.vendorid,
+    };
+    config
+}
+
+/// Pairs of (extension, dependency): the extension can only be enabled if the dependency is.
+const DEPENDENCIES: &[(&str, &str)] = &[
+    ("S", "U"),
+    ("Sstc", "S"),
+    ("Sscofpmf", "S"),
+    ("Sscofpmf", "Zihpm"),
+    ("Svinval", "S"),
+    ("Sv39", "S"),
+    ("Sv48", "Sv39"),
+    ("Sv57", "Sv48"),
+    ("Zabha", "Zaamo"),
+    ("Zcb", "Zca"),
+    ("Zcd", "Zca"),
+    ("Zcd", "FD"),
+    ("Zcf", "Zca"),
+    ("Zcf", "FD"),
+    ("Zfa", "FD"),
+    ("Zfh", "FD"),
+    ("Zfhmin", "FD"),
+    ("Zhinx", "Zfinx"),
+    ("V", "FD"),
+    ("Zvbb", "V"),
+    ("Zvbc", "V"),
+    ("Zvkb", "V"),
+    ("Zvknha", "V"),
+    ("Zvknhb", "V"),
+    ("Zvksh", "V"),
+];
+
+/// Pairs of extensions that can not be enabled at the same time.
+const CONFLICTS: &[(&str, &str)] = &[("Zfinx", "FD")];
+
+/// The number of PMP entries supported by the specification.
+const PMP_COUNTS: &[i128] = &[0, 16, 64];
+
+/// The maximum PMP granularity.
+const MAX_PMP_GRAIN: i128 = 63;
+
+/// An error reported when validating a configuration.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConfigError {
+    /// The extension does not exist (or is not supported by the model).
+    UnknownExtension(String),
+    /// An extension is enabled, but one of its dependencies is not.
+    MissingDependency {
+        extension: &'static str,
+        requires: &'static str,
+    },
+    /// Two incompatible extensions are enabled.
+    Conflict(&'static str, &'static str),
+    /// The extension can not be enabled on RV64.
+    UnsupportedExtension(&'static str),
+    /// The number of PMP entries must be 0, 16, or 64.
+    InvalidPmpCount(i128),
+    /// The PMP granularity must be in range 0 to 63.
+    InvalidPmpGrain(i128),
+    /// The value of `vlen_exp` is not supported.
+    InvalidVlenExp(i128),
+    /// The ISA string is malformed.
+    InvalidIsaString(String),
+    /// The JSON configuration is malformed.
+    InvalidJson(String),
+    /// Keys required by the model are missing from the JSON configuration.
+    MissingKeys(Vec<String>),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::UnknownExtension(name) => write!(f, "unknown extension '{}'", name),
+            ConfigError::MissingDependency {
+                extension,
+                requires,
+            } => write!(f, "extension {} requires {}", extension, requires),
+            ConfigError::Conflict(a, b) => {
+                write!(f, "extensions {} and {} can not be enabled together", a, b)
+            }
+            ConfigError::UnsupportedExtension(name) => {
+                write!(f, "extension {} is not supported on RV64", name)
+            }
+            ConfigError::InvalidPmpCount(count) => write!(
+                f,
+                "invalid PMP count {}, must be one of {:?}",
+                count, PMP_COUNTS
+            ),
+            ConfigError::InvalidPmpGrain(grain) => write!(
+                f,
+                "invalid PMP grain {}, must be between 0 and {}",
+                grain, MAX_PMP_GRAIN
+            ),
+            ConfigError::InvalidVlenExp(vlen_exp) => write!(
+                f,
+                "invalid vlen_exp {}, the available models are compiled with {:?}",
+                vlen_exp,
+                crate::VLEN_EXPS
+            ),
+            ConfigError::InvalidIsaString(reason) => write!(f, "invalid ISA string: {}", reason),
+            ConfigError::InvalidJson(reason) => write!(f, "invalid JSON configuration: {}", reason),
+            ConfigError::MissingKeys(keys) => {
+                write!(f, "missing configuration keys: {}", keys.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Check that a configuration respects the constraints of the specification.
+///
+/// Returns the first violated constraint, if any.
+pub fn validate(config: &raw::Config) -> Result<(), ConfigError> {
+    let enabled = |name| extension_supported(config, name).unwrap_or(false);
+
+    if enabled("Sv32") {
+        return Err(ConfigError::UnsupportedExtension("Sv32"));
+    }
+    for &(extension, requires) in DEPENDENCIES {
+        if enabled(extension) && !enabled(requires) {
+            return Err(ConfigError::MissingDependency {
+                extension,
+                requires,
+            });
+        }
+    }
+    for &(a, b) in CONFLICTS {
+        if enabled(a) && enabled(b) {
+            return Err(ConfigError::Conflict(a, b));
+        }
+    }
+
+    let pmp = &config.memory.pmp;
+    if !PMP_COUNTS.contains(&pmp.count) {
+        return Err(ConfigError::InvalidPmpCount(pmp.count));
+    }
+    if !(0..=MAX_PMP_GRAIN).contains(&pmp.grain) {
+        return Err(ConfigError::InvalidPmpGrain(pmp.grain));
+    }
+    if !crate::VLEN_EXPS.contains(&config.extensions.V.vlen_exp) {
+        return Err(ConfigError::InvalidVlenExp(config.extensions.V.vlen_exp));
+    }
+
+    Ok(())
+}
+
+/// A builder to create custom configurations.
+///
+/// The builder starts from a preset (or [MINIMAL] by default), the configuration is validated
+/// when calling [ConfigBuilder::build].
+///
+/// ```
+/// use softcore_rv64::config::{self, ConfigBuilder};
+///
+/// let config = ConfigBuilder::from_preset(&config::U74)
+///     .enable("Zicond")
+///     .disable("B")
+///     .pmp(64, 2)
+///     .hart_id(1)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct ConfigBuilder {
+    config: raw::Config,
+    error: Option<ConfigError>,
+}
+
+impl ConfigBuilder {
+    /// Creates a builder starting from the [MINIMAL] configuration.
+    pub fn new() -> Self {
+        Self::from_preset(&MINIMAL)
+    }
+
+    /// Creates a builder starting from an existing configuration.
+    pub fn from_preset(preset: &raw::Config) -> Self {
+        ConfigBuilder {
+            config: preset.clone(),
+            error: None,
+        }
+    }
+
+    /// Enable an extension, identified by its name.
+    ///
+    /// The list of valid names is available in [EXTENSIONS].
+    pub fn enable(self, extension: &str) -> Self {
+        self.set_extension(extension, true)
+    }
+
+    /// Disable an extension, identified by its name.
+    ///
+    /// The list of valid names is available in [EXTENSIONS].
+    pub fn disable(self, extension: &str) -> Self {
+        self.set_extension(extension, false)
+    }
+
+    /// Set the number of PMP entries and the PMP granularity.
+    pub fn pmp(mut self, count: i128, grain: i128) -> Self {
+        self.config.memory.pmp = raw::ConfigPmp { count, grain };
+        self
+    }
+
+    /// Set `vlen_exp`, which determines the length of vector registers.
+    ///
+    /// The vector length is fixed when compiling the model, valid values are listed in
+    /// [crate::VLEN_EXPS]. Use [crate::new_any_core] to create a core of the model variant
+    /// matching the configuration.
+    pub fn vlen_exp(mut self, vlen_exp: i128) -> Self {
+        self.config.extensions.V.vlen_exp = vlen_exp;
+        self
+    }
+
+    /// Set the hart ID, as reported by `mhartid`.
+    pub fn hart_id(mut self, hart_id: i128) -> Self {
+        self.config.platform.hartid = hart_id;
+        self
+    }
+
+    /// Set the vendor ID, as reported by `mvendorid`.
+    pub fn vendor_id(mut self, vendor_id: i128) -> Self {
+        self.config.platform.vendorid = vendor_id;
+        self
+    }
+
+    /// Set the architecture ID, as reported by `marchid`.
+    pub fn arch_id(mut self, arch_id: i128) -> Self {
+        self.config.platform.archid = arch_id;
+        self
+    }
+
+    /// Set the implementation ID, as reported by `mimpid`.
+    pub fn imp_id(mut self, imp_id: i128) -> Self {
+        self.config.platform.impid = imp_id;
+        self
+    }
+
+    /// Validate and return the configuration.
+    pub fn build(self) -> Result<raw::Config, ConfigError> {
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+        validate(&self.config)?;
+        Ok(self.config)
+    }
+
+    fn set_extension(mut self, extension: &str, supported: bool) -> Self {
+        match extension_mut(&mut self.config, extension) {
+            Some(flag) => *flag = supported,
+            None => {
+                // Report the first error only
+                self.error
+                    .get_or_insert(ConfigError::UnknownExtension(extension.to_string()));
+            }
+        }
+        self
+    }
+}
+
+impl Default for ConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// —————————————————————————————— ISA Strings ——————————————————————————————— //
+
+/// The canonical order of single-letter extensions, also used to order multi-letter `Z`
+/// extensions by category.
+const CANONICAL_ORDER: &str = "imafdqlcbkjtpvh";
+
+/// Multi-letter extensions that are always supported by the model.
+const ALWAYS_SUPPORTED: &[&str] = &["zicsr"];
+
+/// Supervisor-level extensions, which imply support for S-mode.
+const SUPERVISOR_EXTENSIONS: &[&str] = &[
+    "Sscofpmf", "Sstc", "Sv39", "Sv48", "Sv57", "Svbare", "Svinval",
+];
+
+/// Creates a configuration from an ISA string, such as `rv64imafdc_zicsr_zba_zbb_sv39`.
+///
+/// The single-letter extensions map onto the corresponding configuration extensions (`c` enables
+/// `Zca`, as well as `Zcd` if `d` is present) and multi-letter extensions are matched by name.
+/// Version numbers are accepted but ignored. The ISA string does not describe privilege modes:
+/// supervisor-level extensions (such as `sv39`) imply support for S-mode and U-mode, which can
+/// also be enabled explicitly with the `s` and `u` letters (as reported by Linux).
+///
+/// The other parameters (e.g. PMP) are taken from the [MINIMAL] configuration, the resulting
+/// configuration is validated before being returned.
+pub fn from_isa_string(isa: &str) -> Result<raw::Config, ConfigError> {
+    let lowercase = isa.to_ascii_lowercase();
+    let invalid = |reason: &str| ConfigError::InvalidIsaString(format!("'{}': {}", isa, reason));
+    let rest = lowercase
+        .strip_prefix("rv64")
+        .ok_or_else(|| invalid("only rv64 is supported"))?;
+
+    // The single-letter extensions come first, the first multi-letter extension might not be
+    // separated by an underscore.
+    let mut chunks = rest.split('_');
+    let letters = chunks.next().unwrap_or("");
+    let multi_letter_start = letters
+        .char_indices()
+        .find(|&(idx, c)| {
+            c == 'z'
+                || c == 'x'
+                || (c == 's' && !matches!(letters[idx + 1..].chars().next(), None | Some('u')))
+        })
+        .map(|(idx, _)| idx)
+        .unwrap_or(letters.len());
+    let (letters, first_multi_letter) = letters.split_at(multi_letter_start);
+
+    let mut config = MINIMAL.clone();
+
+    let mut letters = letters.chars().peekable();
+    match letters.next() {
+        Some('i') => (),
+        Some('g') => {
+            for name in ["M", "A", "FD", "Zifencei"] {
+                enable(&mut config, name);
+            }
+        }
+        Some('e') => return Err(invalid("RV64E is not supported")),
+        _ => return Err(invalid("the base ISA must be 'i' or 'g'")),
+    }
+    let (mut f, mut d, mut c) = (false, false, false);
+    while let Some(letter) = letters.next() {
+        match letter {
+            'm' => enable(&mut config, "M"),
+            'a' => enable(&mut config, "A"),
+            'f' => f = true,
+            'd' => d = true,
+            'c' => c = true,
+            'b' => enable(&mut config, "B"),
+            'v' => enable(&mut config, "V"),
+            's' => enable(&mut config, "S"),
+            'u' => enable(&mut config, "U"),
+            _ => return Err(invalid(&format!("unsupported extension '{}'", letter))),
+        }
+        // Skip the version number, if any
+        while letters
+            .next_if(|c| c.is_ascii_digit() || *c == 'p')
+            .is_some()
+        {}
+    }
+    if f != d {
+        return Err(invalid("'f' and 'd' must be enabled together"));
+    }
+    if f {
+        enable(&mut config, "FD");
+    }
+    if c {
+        enable(&mut config, "Zca");
+        if config.extensions.FD.supported {
+            enable(&mut config, "Zcd");
+        }
+    }
+
+    let multi_letters = std::iter::once(first_multi_letter).chain(chunks);
+    for name in multi_letters.filter(|name| !name.is_empty()) {
+        match find_extension(name) {
+            Some(extension) => enable(&mut config, extension),
+            None if ALWAYS_SUPPORTED.contains(&strip_version(name)) => (),
+            None => return Err(ConfigError::UnknownExtension(name.to_string())),
+        }
+    }
+
+    // Infer the supported privilege modes
+    if SUPERVISOR_EXTENSIONS
+        .iter()
+        .any(|ext| extension_supported(&config, ext) == Some(true))
+    {
+        enable(&mut config, "S");
+    }
+    if config.extensions.S.supported {
+        enable(&mut config, "U");
+    }
+
+    validate(&config)?;
+    Ok(config)
+}
+
+/// Returns the ISA string of a configuration, such as `rv64imafdc_zicsr_zba_zbb_sv39`.
+///
+/// This is the inverse of [from_isa_string]. S-mode and U-mode support is reported with the `s`
+/// and `u` letters only if not already implied by other extensions.
+pub fn to_isa_string(config: &raw::Config) -> String {
+    let ext = &config.extensions;
+    let compressed = ext.Zca.supported && ext.Zcd.supported == ext.FD.supported;
+    let supervisor_implied = SUPERVISOR_EXTENSIONS
+        .iter()
+        .any(|name| extension_supported(config, name) == Some(true));
+
+    let mut isa = String::from("rv64i");
+    let letters = [
+        ('m', ext.M.supported),
+        ('a', ext.A.supported),
+        ('f', ext.FD.supported),
+        ('d', ext.FD.supported),
+        ('c', compressed),
+        ('b', ext.B.supported),
+        ('v', ext.V.supported),
+        ('s', ext.S.supported && !supervisor_implied),
+        ('u', ext.U.supported && !ext.S.supported),
+    ];
+    for (letter, supported) in letters {
+        if supported {
+            isa.push(letter);
+        }
+    }
+
+    let mut multi_letters: Vec<&str> = EXTENSIONS
+        .iter()
+        .copied()
+        .filter(|name| name.starts_with('Z') || (name.starts_with('S') && name.len() > 1))
+        .filter(|name| extension_supported(config, name) == Some(true))
+        .filter(|name| !(compressed && (*name == "Zca" || *name == "Zcd")))
+        .chain(ALWAYS_SUPPORTED.iter().copied())
+        .collect();
+    multi_letters.sort_by_key(|name| multi_letter_order(name));
+    for name in multi_letters {
+        isa.push('_');
+        isa.push_str(&name.to_ascii_lowercase());
+    }
+
+    isa
+}
+
+/// Enable an extension, the extension name must be valid.
+fn enable(config: &mut raw::Config, name: &str) {
+    *extension_mut(config, name).expect("Invalid extension name") = true;
+}
+
+/// Find a multi-letter extension by name, ignoring case and version number.
+fn find_extension(name: &str) -> Option<&'static str> {
+    let find = |name: &str| {
+        EXTENSIONS
+            .iter()
+            .copied()
+            .filter(|ext| ext.starts_with('Z') || ext.starts_with('S'))
+            .find(|ext| ext.eq_ignore_ascii_case(name))
+    };
+    find(name).or_else(|| find(strip_version(name)))
+}
+
+/// Removes the version suffix (e.g. `2p0`) from an extension name.
+fn strip_version(name: &str) -> &str {
+    match name.rsplit_once('p') {
+        Some((base, minor)) if minor.chars().all(|c| c.is_ascii_digit()) => {
+            base.trim_end_matches(|c: char| c.is_ascii_digit())
+        }
+        _ => name,
+    }
+}
+
+/// The canonical order of multi-letter extensions: `Z` extensions ordered by category, then `S`
+/// extensions, each in alphabetical order.
+fn multi_letter_order(name: &str) -> (usize, usize, String) {
+    let name = name.to_ascii_lowercase();
+    let mut chars = name.chars();
+    match (chars.next(), chars.next()) {
+        (Some('z'), Some(category)) => {
+            let category = CANONICAL_ORDER
+                .find(category)
+                .unwrap_or(CANONICAL_ORDER.len());
+            (0, category, name)
+        }
+        _ => (1, 0, name),
+    }
+}
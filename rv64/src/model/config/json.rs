@@ -0,0 +1,335 @@
+//! Configurations in the sail-riscv JSON format.
+//!
+//! The C emulator of the specification is configured with a JSON file, whose structure mirrors
+//! [raw::Config]. Those files also contain keys that are not used by this model (such as the
+//! memory map of the platform), which are reported but otherwise ignored.
+
+use super::{ConfigError, raw};
+use super::{EXTENSIONS, MINIMAL, extension_mut, validate};
+use serde_json::{Map, Value};
+use softcore_prelude::serde::Deserialize;
+use softcore_prelude::{BitVector, SupportedWidth, Width};
+use std::collections::BTreeMap;
+
+/// A configuration loaded from a JSON file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JsonConfig {
+    /// The configuration.
+    pub config: raw::Config,
+    /// The keys of the JSON file that are not used by the model, such as `platform.clint`.
+    pub unknown_keys: Vec<String>,
+}
+
+/// Load a configuration in the sail-riscv JSON format.
+///
+/// All the keys used by the model must be present, missing keys are reported all at once with
+/// [ConfigError::MissingKeys]. The configuration is validated before being returned.
+pub fn from_json(json: &str) -> Result<JsonConfig, ConfigError> {
+    let root: Root =
+        serde_json::from_str(json).map_err(|err| ConfigError::InvalidJson(err.to_string()))?;
+    let mut config = MINIMAL;
+    let mut keys = Keys::default();
+    keys.unknown("", root.unknown);
+
+    if let Some(base) = keys.required("", "base", root.base) {
+        keys.unknown("base", base.unknown);
+        let base_cfg = &mut config.base;
+        keys.load(
+            "base",
+            "writable_fiom",
+            base.writable_fiom,
+            &mut base_cfg.writable_fiom,
+        );
+        keys.load(
+            "base",
+            "writable_misa",
+            base.writable_misa,
+            &mut base_cfg.writable_misa,
+        );
+        let counters = &mut base_cfg.writable_hpm_counters;
+        keys.load(
+            "base",
+            "writable_hpm_counters",
+            base.writable_hpm_counters,
+            counters,
+        );
+    }
+
+    if let Some(mut extensions) = keys.required("", "extensions", root.extensions) {
+        for name in EXTENSIONS {
+            let Some(extension) = keys.required("extensions", name, extensions.remove(*name))
+            else {
+                continue;
+            };
+            let path = format!("extensions.{}", name);
+            keys.unknown(&path, extension.unknown);
+            let supported = extension_mut(&mut config, name).unwrap();
+            keys.load(&path, "supported", extension.supported, supported);
+            match (*name, extension.vlen_exp) {
+                ("V", vlen_exp) => {
+                    let field = &mut config.extensions.V.vlen_exp;
+                    keys.load(&path, "vlen_exp", vlen_exp, field);
+                }
+                (_, Some(_)) => keys.unknown.push(format!("{}.vlen_exp", path)),
+                (_, None) => (),
+            }
+        }
+        for name in extensions.keys() {
+            keys.unknown.push(format!("extensions.{}", name));
+        }
+    }
+
+    if let Some(memory) = keys.required("", "memory", root.memory) {
+        keys.unknown("memory", memory.unknown);
+        if let Some(pmp) = keys.required("memory", "pmp", memory.pmp) {
+            keys.unknown("memory.pmp", pmp.unknown);
+            let pmp_cfg = &mut config.memory.pmp;
+            keys.load("memory.pmp", "count", pmp.count, &mut pmp_cfg.count);
+            keys.load("memory.pmp", "grain", pmp.grain, &mut pmp_cfg.grain);
+        }
+    }
+
+    if let Some(platform) = keys.required("", "platform", root.platform) {
+        keys.unknown("platform", platform.unknown);
+        let platform_cfg = &mut config.platform;
+        keys.load(
+            "platform",
+            "archid",
+            platform.archid,
+            &mut platform_cfg.archid,
+        );
+        keys.load(
+            "platform",
+            "hartid",
+            platform.hartid,
+            &mut platform_cfg.hartid,
+        );
+        keys.load("platform", "impid", platform.impid, &mut platform_cfg.impid);
+        keys.load(
+            "platform",
+            "vendorid",
+            platform.vendorid,
+            &mut platform_cfg.vendorid,
+        );
+    }
+
+    if !keys.missing.is_empty() {
+        return Err(ConfigError::MissingKeys(keys.missing));
+    }
+    validate(&config)?;
+    Ok(JsonConfig {
+        config,
+        unknown_keys: keys.unknown,
+    })
+}
+
+// ——————————————————————————— JSON Configuration ——————————————————————————— //
+
+/// The root of a JSON configuration.
+///
+/// Keys are optional so that all missing keys can be reported at once, and keys unused by the
+/// model are collected in `unknown`.
+#[derive(Deserialize)]
+#[serde(crate = "softcore_prelude::serde")]
+struct Root {
+    base: Option<Base>,
+    extensions: Option<BTreeMap<String, Extension>>,
+    memory: Option<Memory>,
+    platform: Option<Platform>,
+    #[serde(flatten)]
+    unknown: Map<String, Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "softcore_prelude::serde")]
+struct Base {
+    writable_fiom: Option<bool>,
+    writable_hpm_counters: Option<Bits<32>>,
+    writable_misa: Option<bool>,
+    #[serde(flatten)]
+    unknown: Map<String, Value>,
+}
+
+/// An extension, only the vector extension has a `vlen_exp`.
+#[derive(Deserialize)]
+#[serde(crate = "softcore_prelude::serde")]
+struct Extension {
+    supported: Option<bool>,
+    vlen_exp: Option<Int>,
+    #[serde(flatten)]
+    unknown: Map<String, Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "softcore_prelude::serde")]
+struct Memory {
+    pmp: Option<Pmp>,
+    #[serde(flatten)]
+    unknown: Map<String, Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "softcore_prelude::serde")]
+struct Pmp {
+    count: Option<Int>,
+    grain: Option<Int>,
+    #[serde(flatten)]
+    unknown: Map<String, Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "softcore_prelude::serde")]
+struct Platform {
+    archid: Option<Int>,
+    hartid: Option<Int>,
+    impid: Option<Int>,
+    vendorid: Option<Int>,
+    #[serde(flatten)]
+    unknown: Map<String, Value>,
+}
+
+// ————————————————————————————————— Keys ——————————————————————————————————— //
+
+/// Keeps track of the unknown and missing keys while reading the JSON configuration.
+#[derive(Default)]
+struct Keys {
+    unknown: Vec<String>,
+    missing: Vec<String>,
+}
+
+impl Keys {
+    /// Record the keys of `object` as unknown.
+    fn unknown(&mut self, path: &str, object: Map<String, Value>) {
+        for key in object.keys() {
+            self.unknown.push(join(path, key));
+        }
+    }
+
+    /// Returns the value of a required key, recording the key as missing if absent.
+    fn required<T>(&mut self, path: &str, key: &str, value: Option<T>) -> Option<T> {
+        if value.is_none() {
+            self.missing.push(join(path, key));
+        }
+        value
+    }
+
+    /// Load the value of a required key into `field`, if present.
+    fn load<T, U: Into<T>>(&mut self, path: &str, key: &str, value: Option<U>, field: &mut T) {
+        if let Some(value) = self.required(path, key, value) {
+            *field = value.into();
+        }
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+// —————————————————————————————— JSON Values ——————————————————————————————— //
+
+/// Integers are either JSON numbers or strings, which might be in hexadecimal or binary.
+#[derive(Deserialize)]
+#[serde(crate = "softcore_prelude::serde", try_from = "IntRepr")]
+struct Int(i128);
+
+#[derive(Deserialize)]
+#[serde(crate = "softcore_prelude::serde", untagged)]
+enum IntRepr {
+    Signed(i64),
+    Unsigned(u64),
+    String(String),
+}
+
+impl TryFrom<IntRepr> for Int {
+    type Error = String;
+
+    fn try_from(repr: IntRepr) -> Result<Self, Self::Error> {
+        match repr {
+            IntRepr::Signed(value) => Ok(Int(value.into())),
+            IntRepr::Unsigned(value) => Ok(Int(value.into())),
+            IntRepr::String(string) => parse_int(&string)
+                .map(Int)
+                .ok_or_else(|| format!("invalid integer '{}'", string)),
+        }
+    }
+}
+
+impl From<Int> for i128 {
+    fn from(value: Int) -> Self {
+        value.0
+    }
+}
+
+/// Bitvectors are either integers or objects with a `len` and a `value`, such as
+/// `{ "len": 32, "value": "0xFFFFFFFF" }`.
+#[derive(Deserialize)]
+#[serde(crate = "softcore_prelude::serde", try_from = "BitsRepr")]
+struct Bits<const N: i128>(BitVector<N>)
+where
+    Width<N>: SupportedWidth;
+
+#[derive(Deserialize)]
+#[serde(crate = "softcore_prelude::serde", untagged)]
+enum BitsRepr {
+    Int(Int),
+    Sized(SizedBits),
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "softcore_prelude::serde", deny_unknown_fields)]
+struct SizedBits {
+    len: Int,
+    value: Int,
+}
+
+impl<const N: i128> TryFrom<BitsRepr> for Bits<N>
+where
+    Width<N>: SupportedWidth,
+{
+    type Error = String;
+
+    fn try_from(repr: BitsRepr) -> Result<Self, Self::Error> {
+        let value = match repr {
+            BitsRepr::Int(Int(value)) => value,
+            BitsRepr::Sized(SizedBits { len: Int(len), .. }) if len != N => {
+                return Err(format!("expected a {} bits bitvector, got {} bits", N, len));
+            }
+            BitsRepr::Sized(SizedBits {
+                value: Int(value), ..
+            }) => value,
+        };
+        if value < 0 || (N < 128 && value >> N != 0) {
+            return Err(format!("value {:#x} does not fit in {} bits", value, N));
+        }
+        Ok(Bits(BitVector::from_u128(value as u128)))
+    }
+}
+
+impl<const N: i128> From<Bits<N>> for BitVector<N>
+where
+    Width<N>: SupportedWidth,
+{
+    fn from(value: Bits<N>) -> Self {
+        value.0
+    }
+}
+
+fn parse_int(string: &str) -> Option<i128> {
+    let string = string.replace('_', "");
+    let (negative, digits) = match string.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, string.as_str()),
+    };
+    let value = if let Some(hex) = digits.strip_prefix("0x").or(digits.strip_prefix("0X")) {
+        i128::from_str_radix(hex, 16).ok()?
+    } else if let Some(bin) = digits.strip_prefix("0b").or(digits.strip_prefix("0B")) {
+        i128::from_str_radix(bin, 2).ok()?
+    } else {
+        digits.parse().ok()?
+    };
+    Some(if negative { -value } else { value })
+}
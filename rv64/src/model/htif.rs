@@ -0,0 +1,202 @@
+//! Host-Target Interface
+//!
+//! HTIF is the interface used by the riscv-tests and proxy kernel binaries to communicate with the
+//! host through the `tohost` and `fromhost` registers. The platform model of the specification
+//! relies on external functions that are not translated by the Rust back-end. Instead, the
+//! [Platform](super::Platform) records commands written to `tohost` by the specification, and this
+//! module processes them after each step, following the `htif_store` function of the
+//! specification.
+//!
+//! Two devices are supported:
+//! - The syscall proxy (device 0): a payload with its lowest bit set signals the end of the
+//!   execution with the remaining bits as exit code. Otherwise the payload points to a syscall
+//!   descriptor, of which only `write` to stdout or stderr and `exit` are supported.
+//! - The console (device 1): the `putchar` command writes the lowest byte of the payload, and is
+//!   acknowledged by echoing the command with the byte in `fromhost`.
+//!
+//! Console output is buffered and can be retrieved with [Core::htif_console].
+
+use super::raw::Core;
+use softcore_prelude::bv;
+
+const DEVICE_SYSCALL_PROXY: u64 = 0;
+const DEVICE_CONSOLE: u64 = 1;
+const CONSOLE_PUTCHAR: u64 = 1;
+
+const SYS_WRITE: u64 = 64;
+const SYS_EXIT: u64 = 93;
+const EFAULT: i64 = 14;
+const ENOSYS: i64 = 38;
+
+/// The largest buffer accepted by the `write` syscall, larger writes are truncated.
+const MAX_WRITE_LEN: u64 = 1 << 20;
+
+/// An HTIF device, with `tohost` and `fromhost` registers living in regular memory.
+///
+/// Writes to `tohost` are recorded as commands for the host to process once complete, that is
+/// either after a 64 bits write or after a write to its upper 32 bits.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(
+        softcore_prelude::serde::Serialize,
+        softcore_prelude::serde::Deserialize
+    ),
+    serde(crate = "softcore_prelude::serde")
+)]
+pub(super) struct Htif {
+    tohost: u64,
+    fromhost: u64,
+    console: Vec<u8>,
+    /// The last command written to `tohost` and not yet processed by the host.
+    pending: Option<u64>,
+}
+
+/// The status of the program running on a core, as reported through HTIF.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HtifStatus {
+    /// The program has not signaled completion yet.
+    Running,
+    /// The program exited with code 0.
+    Pass,
+    /// The program exited with a non-zero code, for riscv-tests this is the failing test number.
+    Fail(u64),
+}
+
+impl Core {
+    /// Attach an HTIF device with `tohost` and `fromhost` registers at the given physical
+    /// addresses.
+    ///
+    /// Those addresses are usually given by the `tohost` and `fromhost` symbols of the binary.
+    /// This replaces the current device, if any, and resets the completion status.
+    pub fn set_htif(&mut self, tohost: u64, fromhost: u64) {
+        self.platform.htif = Some(Htif {
+            tohost,
+            fromhost,
+            console: Vec::new(),
+            pending: None,
+        });
+        self.htif_tohost = bv(0);
+        self.htif_done = false;
+        self.htif_exit_code = bv(0);
+        self.htif_cmd_write = false;
+        self.htif_payload_writes = bv(0);
+    }
+
+    /// Returns the completion status reported by the program through HTIF.
+    pub fn htif_status(&self) -> HtifStatus {
+        match (self.htif_done, self.htif_exit_code.bits()) {
+            (false, _) => HtifStatus::Running,
+            (true, 0) => HtifStatus::Pass,
+            (true, code) => HtifStatus::Fail(code),
+        }
+    }
+
+    /// Returns the output written to the HTIF console so far.
+    pub fn htif_console(&self) -> &[u8] {
+        match &self.platform.htif {
+            Some(htif) => &htif.console,
+            None => &[],
+        }
+    }
+
+    /// Record the pending HTIF command, if a write issued by the specification completes one.
+    pub(super) fn notify_htif(&mut self, addr: u64, width: usize) {
+        let Some(htif) = &self.platform.htif else {
+            return;
+        };
+        let command_complete = (addr == htif.tohost && width == 8)
+            || (addr == htif.tohost.wrapping_add(4) && width == 4);
+        if command_complete {
+            let command = self.mem.read(htif.tohost, 8).unwrap_or(0);
+            if let Some(htif) = &mut self.platform.htif {
+                htif.pending = Some(command);
+            }
+        }
+    }
+
+    /// Process the pending HTIF command, if any.
+    pub(super) fn process_htif(&mut self) {
+        let Some(htif) = &mut self.platform.htif else {
+            return;
+        };
+        let Some(command) = htif.pending.take() else {
+            return;
+        };
+        let (tohost, fromhost) = (htif.tohost, htif.fromhost);
+
+        self.htif_tohost = bv(command);
+        self.htif_cmd_write = false;
+        let device = command >> 56;
+        let cmd = (command >> 48) & 0xff;
+        let payload = command & 0xffff_ffff_ffff;
+
+        match device {
+            DEVICE_SYSCALL_PROXY if payload & 1 == 1 => self.htif_exit(payload >> 1),
+            DEVICE_SYSCALL_PROXY => {
+                let ret = self.htif_syscall(payload);
+                self.htif_write(payload, ret as u64);
+                self.htif_write(fromhost, 1);
+            }
+            DEVICE_CONSOLE if cmd == CONSOLE_PUTCHAR => {
+                let byte = payload as u8;
+                self.htif_putchar(&[byte]);
+                self.htif_write(fromhost, command >> 48 << 48 | 0x100 | byte as u64);
+            }
+            _ => (),
+        }
+
+        // The host acknowledges the command by clearing `tohost`.
+        self.htif_write(tohost, 0);
+    }
+
+    /// Execute a proxied syscall, described by eight double words at `addr`.
+    ///
+    /// Returns `-EFAULT` if the descriptor or the buffer of a `write` can not be read.
+    fn htif_syscall(&mut self, addr: u64) -> i64 {
+        let mut args = [0; 8];
+        for (i, arg) in args.iter_mut().enumerate() {
+            match self.mem.read(addr.wrapping_add(8 * i as u64), 8) {
+                Ok(value) => *arg = value,
+                Err(_) => return -EFAULT,
+            }
+        }
+
+        match args[0] {
+            SYS_WRITE if args[1] == 1 || args[1] == 2 => {
+                let (buf, len) = (args[2], args[3].min(MAX_WRITE_LEN));
+                let bytes: Result<Vec<u8>, _> = (0..len)
+                    .map(|i| self.mem.read(buf.wrapping_add(i), 1).map(|byte| byte as u8))
+                    .collect();
+                match bytes {
+                    Ok(bytes) => {
+                        self.htif_putchar(&bytes);
+                        len as i64
+                    }
+                    Err(_) => -EFAULT,
+                }
+            }
+            SYS_EXIT => {
+                self.htif_exit(args[1]);
+                0
+            }
+            _ => -ENOSYS,
+        }
+    }
+
+    fn htif_exit(&mut self, exit_code: u64) {
+        self.htif_done = true;
+        self.htif_exit_code = bv(exit_code);
+    }
+
+    fn htif_putchar(&mut self, bytes: &[u8]) {
+        if let Some(htif) = &mut self.platform.htif {
+            htif.console.extend_from_slice(bytes);
+        }
+    }
+
+    fn htif_write(&mut self, addr: u64, value: u64) {
+        // HTIF registers are backed by regular memory, writes are not expected to fail.
+        let _ = self.mem.write(addr, 8, value);
+    }
+}
@@ -0,0 +1,262 @@
+//! ELF loader
+//!
+//! This module parses RV64 ELF binaries and loads them into the memory of a core. Only the parts
+//! of the format required to run a binary are supported: the entry point, the loadable segments,
+//! and the `tohost` and `fromhost` symbols used by the HTIF device (see [Core::set_htif]).
+
+use super::raw::Core;
+use softcore_prelude::bv;
+use std::fmt;
+
+const ELF_MAGIC: &[u8] = b"\x7fELF";
+const ELFCLASS64: u8 = 2;
+const ELFDATA2LSB: u8 = 1;
+const EM_RISCV: u16 = 243;
+const PT_LOAD: u32 = 1;
+const SHT_SYMTAB: u32 = 2;
+
+const EHDR_SIZE: usize = 64;
+const PHDR_SIZE: usize = 56;
+const SHDR_SIZE: usize = 64;
+const SYM_SIZE: usize = 24;
+
+/// The offset of `fromhost` relative to `tohost` in binaries that only define `tohost`.
+///
+/// The riscv-tests environment places both registers on their own 64 bytes aligned slot.
+const FROMHOST_OFFSET: u64 = 0x40;
+
+/// The largest segment accepted by the loader, larger segments are most likely the sign of a
+/// corrupted binary.
+pub const MAX_SEGMENT_SIZE: u64 = 1 << 30;
+
+/// A parsed ELF binary.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Elf<'a> {
+    /// The virtual address of the entry point.
+    pub entry: u64,
+    /// The loadable segments.
+    pub segments: Vec<Segment<'a>>,
+    /// The address of the `tohost` symbol, if any.
+    pub tohost: Option<u64>,
+    /// The address of the `fromhost` symbol, if any.
+    pub fromhost: Option<u64>,
+}
+
+/// A loadable segment of an ELF binary.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Segment<'a> {
+    /// The physical address of the segment.
+    pub addr: u64,
+    /// The content of the segment in the file.
+    pub data: &'a [u8],
+    /// The size of the segment in memory, bytes past the end of `data` are zeroed.
+    pub mem_size: u64,
+}
+
+/// An error reported when loading an ELF binary.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LoadError {
+    /// The file does not start with the ELF magic number.
+    NotElf,
+    /// The binary is not a little-endian RV64 binary.
+    Unsupported(&'static str),
+    /// A header or segment points outside of the file.
+    Truncated,
+    /// A segment is larger in the file than in memory.
+    InvalidSegment { addr: u64 },
+    /// A segment is larger than [MAX_SEGMENT_SIZE] in memory.
+    SegmentTooLarge { addr: u64, mem_size: u64 },
+    /// The memory backend rejected a write at the given address.
+    AccessFault(u64),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::NotElf => write!(f, "not an ELF file"),
+            LoadError::Unsupported(reason) => write!(f, "unsupported ELF file: {}", reason),
+            LoadError::Truncated => write!(f, "truncated ELF file"),
+            LoadError::InvalidSegment { addr } => {
+                write!(
+                    f,
+                    "segment at {:#x} is larger in the file than in memory",
+                    addr
+                )
+            }
+            LoadError::SegmentTooLarge { addr, mem_size } => {
+                write!(
+                    f,
+                    "segment at {:#x} is too large ({:#x} bytes)",
+                    addr, mem_size
+                )
+            }
+            LoadError::AccessFault(addr) => write!(f, "access fault while loading at {:#x}", addr),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+/// Load an ELF binary into the memory of a core.
+///
+/// The loadable segments are copied at their physical address and the PC is set to the entry
+/// point. If the binary defines a `tohost` symbol, an HTIF device is attached to the core.
+pub fn load<'a>(core: &mut Core, bytes: &'a [u8]) -> Result<Elf<'a>, LoadError> {
+    let elf = Elf::parse(bytes)?;
+    for segment in &elf.segments {
+        core.mem
+            .write_bytes(segment.addr, segment.data)
+            .map_err(|fault| LoadError::AccessFault(fault.addr))?;
+        let bss = segment.addr.wrapping_add(segment.data.len() as u64);
+        zero(core, bss, segment.mem_size - segment.data.len() as u64)?;
+    }
+
+    core.PC = bv(elf.entry);
+    if let Some(tohost) = elf.tohost {
+        let fromhost = elf.fromhost.unwrap_or(tohost.wrapping_add(FROMHOST_OFFSET));
+        core.set_htif(tohost, fromhost);
+    }
+    Ok(elf)
+}
+
+impl<'a> Elf<'a> {
+    /// Parse an ELF binary.
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, LoadError> {
+        if !bytes.starts_with(ELF_MAGIC) {
+            return Err(LoadError::NotElf);
+        }
+        let header = slice(bytes, 0, EHDR_SIZE)?;
+        if header[4] != ELFCLASS64 {
+            return Err(LoadError::Unsupported("not a 64 bits binary"));
+        }
+        if header[5] != ELFDATA2LSB {
+            return Err(LoadError::Unsupported("not a little-endian binary"));
+        }
+        if read_u16(header, 18) != EM_RISCV {
+            return Err(LoadError::Unsupported("not a RISC-V binary"));
+        }
+
+        let entry = read_u64(header, 24);
+        let phoff = read_u64(header, 32) as usize;
+        let shoff = read_u64(header, 40) as usize;
+        let phentsize = read_u16(header, 54) as usize;
+        let phnum = read_u16(header, 56) as usize;
+        let shentsize = read_u16(header, 58) as usize;
+        let shnum = read_u16(header, 60) as usize;
+        if phnum > 0 && phentsize < PHDR_SIZE {
+            return Err(LoadError::Unsupported("program headers are too small"));
+        }
+        if shnum > 0 && shentsize < SHDR_SIZE {
+            return Err(LoadError::Unsupported("section headers are too small"));
+        }
+
+        let mut segments = Vec::new();
+        for i in 0..phnum {
+            let phdr = slice(bytes, phoff.saturating_add(i * phentsize), PHDR_SIZE)?;
+            if read_u32(phdr, 0) != PT_LOAD {
+                continue;
+            }
+            let offset = read_u64(phdr, 8) as usize;
+            let addr = read_u64(phdr, 24);
+            let file_size = read_u64(phdr, 32) as usize;
+            let mem_size = read_u64(phdr, 40);
+            if file_size as u64 > mem_size {
+                return Err(LoadError::InvalidSegment { addr });
+            }
+            if mem_size > MAX_SEGMENT_SIZE {
+                return Err(LoadError::SegmentTooLarge { addr, mem_size });
+            }
+            segments.push(Segment {
+                addr,
+                data: slice(bytes, offset, file_size)?,
+                mem_size,
+            });
+        }
+
+        let mut elf = Elf {
+            entry,
+            segments,
+            tohost: None,
+            fromhost: None,
+        };
+        for i in 0..shnum {
+            let shdr = slice(bytes, shoff.saturating_add(i * shentsize), SHDR_SIZE)?;
+            if read_u32(shdr, 4) != SHT_SYMTAB {
+                continue;
+            }
+            let symbols = slice(
+                bytes,
+                read_u64(shdr, 24) as usize,
+                read_u64(shdr, 32) as usize,
+            )?;
+            let strtab_index = read_u32(shdr, 40) as usize;
+            let strtab_hdr = slice(
+                bytes,
+                shoff.saturating_add(strtab_index * shentsize),
+                SHDR_SIZE,
+            )?;
+            let strtab = slice(
+                bytes,
+                read_u64(strtab_hdr, 24) as usize,
+                read_u64(strtab_hdr, 32) as usize,
+            )?;
+            for symbol in symbols.chunks_exact(SYM_SIZE) {
+                let name = symbol_name(strtab, read_u32(symbol, 0) as usize)?;
+                match name {
+                    b"tohost" => elf.tohost = Some(read_u64(symbol, 8)),
+                    b"fromhost" => elf.fromhost = Some(read_u64(symbol, 8)),
+                    _ => (),
+                }
+            }
+        }
+
+        Ok(elf)
+    }
+}
+
+// ———————————————————————————————— Helpers ————————————————————————————————— //
+
+/// Zero `len` bytes of memory starting at `addr`, using aligned double word writes where
+/// possible.
+fn zero(core: &mut Core, addr: u64, len: u64) -> Result<(), LoadError> {
+    let end = addr.wrapping_add(len);
+    let mut addr = addr;
+    while addr != end {
+        let width = if addr.is_multiple_of(8) && end.wrapping_sub(addr) >= 8 {
+            8
+        } else {
+            1
+        };
+        core.mem
+            .write(addr, width, 0)
+            .map_err(|fault| LoadError::AccessFault(fault.addr))?;
+        addr = addr.wrapping_add(width as u64);
+    }
+    Ok(())
+}
+
+fn slice(bytes: &[u8], offset: usize, len: usize) -> Result<&[u8], LoadError> {
+    let end = offset.checked_add(len).ok_or(LoadError::Truncated)?;
+    bytes.get(offset..end).ok_or(LoadError::Truncated)
+}
+
+fn symbol_name(strtab: &[u8], offset: usize) -> Result<&[u8], LoadError> {
+    let name = strtab.get(offset..).ok_or(LoadError::Truncated)?;
+    let len = name
+        .iter()
+        .position(|c| *c == 0)
+        .ok_or(LoadError::Truncated)?;
+    Ok(&name[..len])
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes(bytes[offset..offset + 2].try_into().unwrap())
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
@@ -0,0 +1,148 @@
+//! The platform attached to a core.
+//!
+//! The specification relies on the platform for the side effects it does not model, such as the
+//! devices living in the physical address space. The Rust back-end attaches a [Platform] to the
+//! core: it is notified of executed instructions, register and CSR writes, and branches, and
+//! physical memory writes issued by the specification go through [Core::write_mem] so that
+//! writes to devices can be intercepted. Address translations go through [Core::translate_addr],
+//! so that traces can report the virtual address of data accesses.
+
+use super::htif::Htif;
+use super::raw::{self, AccessType, Core, ExceptionType, TR_Result, ast, physaddr, virtaddr};
+use super::raw::{fregidx, regidx, vregidx};
+use super::trace::{CsrId, RegisterWrite, Trace};
+use softcore_prelude::{BitVector, SupportedWidth, Width};
+
+/// The platform attached to a core: the HTIF device and the trace.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(
+        softcore_prelude::serde::Serialize,
+        softcore_prelude::serde::Deserialize
+    ),
+    serde(crate = "softcore_prelude::serde")
+)]
+pub struct Platform {
+    pub(super) htif: Option<Htif>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(super) trace: Trace,
+}
+
+impl Platform {
+    pub const fn new() -> Self {
+        Platform {
+            htif: None,
+            trace: Trace::new(),
+        }
+    }
+
+    // The following functions are called from the translated specification.
+
+    pub fn instr_announce(&mut self, instr: ast) {
+        self.trace.instr_announce(instr);
+    }
+
+    pub fn x_write<const N: i128>(&mut self, reg: regidx, value: BitVector<N>)
+    where
+        Width<N>: SupportedWidth,
+    {
+        // Writes to x0 are discarded.
+        if reg.bits() != 0 {
+            let write = RegisterWrite::X(reg.bits(), value.bits());
+            self.trace.reg_write(write);
+        }
+    }
+
+    pub fn f_write<const N: i128>(&mut self, reg: fregidx, value: BitVector<N>)
+    where
+        Width<N>: SupportedWidth,
+    {
+        self.trace
+            .reg_write(RegisterWrite::F(reg.bits(), value.bits()));
+    }
+
+    pub fn v_write<const N: i128>(&mut self, reg: vregidx, value: BitVector<N>)
+    where
+        Width<N>: SupportedWidth,
+    {
+        if self.trace.is_enabled() {
+            let bytes = value
+                .words()
+                .iter()
+                .flat_map(|word| word.to_le_bytes())
+                .take(N as usize / 8)
+                .collect();
+            self.trace.reg_write(RegisterWrite::V(reg.bits(), bytes));
+        }
+    }
+
+    pub fn csr_id_write<const N: i128>(&mut self, csr: BitVector<12>, value: BitVector<N>)
+    where
+        Width<N>: SupportedWidth,
+    {
+        self.trace
+            .csr_write(CsrId::Address(csr.bits()), value.bits());
+    }
+
+    pub fn csr_name_write<const N: i128>(&mut self, name: &'static str, value: BitVector<N>)
+    where
+        Width<N>: SupportedWidth,
+    {
+        self.trace.csr_write(CsrId::Name(name), value.bits());
+    }
+
+    pub fn branch_announce<const N: i128>(&mut self, _size: i128, target: BitVector<N>)
+    where
+        Width<N>: SupportedWidth,
+    {
+        self.trace.branch_announce(target.bits());
+    }
+}
+
+impl Core {
+    // The following functions are called from the translated specification, in place of the
+    // physical memory write and of the address translation of the specification.
+
+    pub(crate) fn write_mem<const A: i128, const N: i128>(
+        &mut self,
+        addr: BitVector<A>,
+        width: i128,
+        data: BitVector<N>,
+    ) -> bool
+    where
+        Width<A>: SupportedWidth,
+        Width<N>: SupportedWidth,
+    {
+        let performed = self.mem.write_mem(addr, width, data);
+        if performed {
+            self.notify_htif(addr.bits(), width as usize);
+        }
+        performed
+    }
+
+    pub(crate) fn translate_addr(
+        &mut self,
+        vaddr: virtaddr,
+        access_type: AccessType<()>,
+    ) -> TR_Result<physaddr, ExceptionType> {
+        if !self.platform.trace.is_enabled() {
+            return raw::translateAddr(self, vaddr, access_type);
+        }
+
+        // The accesses recorded so far are performed on the previously translated address, those
+        // performed during the translation are page table walks.
+        let accesses = self.mem.take_accesses();
+        self.platform.trace.mem_accesses(accesses);
+        let result = raw::translateAddr(self, vaddr, access_type);
+        self.mem.take_accesses();
+
+        let virtaddr::Virtaddr(vaddr) = vaddr;
+        let paddr = match result {
+            TR_Result::TR_Address((physaddr::Physaddr(paddr), _)) => Some(paddr.bits()),
+            TR_Result::TR_Failure(_) => None,
+        };
+        self.platform.trace.translation(vaddr.bits(), paddr);
+        result
+    }
+}
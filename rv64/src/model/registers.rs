@@ -0,0 +1,1235 @@
+//! The RISC-V registers
+
+use super::raw::{self, Privilege, fregidx, regidx, vregidx};
+use softcore_prelude::{BitVector, bv};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// The type of general purpose registers
+pub type GeneralRegister = regidx;
+
+/// The X0 register.
+///
+/// This register is hardwired to zero.
+pub const X0: regidx = regidx::Regidx(BitVector::new(0));
+
+/// The ZERO (X0) register.
+///
+/// This register is hardwired to zero.
+pub const ZERO: regidx = X0;
+
+/// The X1 (RA) register.
+///
+/// Used for the return address in the RISC-V ABI.
+pub const X1: regidx = regidx::Regidx(BitVector::new(1));
+
+/// The RA (X1) register.
+///
+/// Used for the return address in the RISC-V ABI.
+pub const RA: regidx = X1;
+
+/// The X2 (SP) register.
+///
+/// Used for the stack pointer in the RISC-V ABI.
+pub const X2: regidx = regidx::Regidx(BitVector::new(2));
+
+/// The SP (X2) register.
+///
+/// Used for the stack pointer in the RISC-V ABI.
+pub const SP: regidx = X2;
+
+/// The X3 (GP) register.
+///
+/// Used for the global pointer in the RISC-V ABI.
+pub const X3: regidx = regidx::Regidx(BitVector::new(3));
+
+/// The GP (X3) register.
+///
+/// Used for the global pointer in the RISC-V ABI.
+pub const GP: regidx = X3;
+
+/// The X4 (TP) register.
+///
+/// Used for the thread pointer in the RISC-V ABI.
+pub const X4: regidx = regidx::Regidx(BitVector::new(4));
+
+/// The TP (X4) register.
+///
+/// Used for the thread pointer in the RISC-V ABI.
+pub const TP: regidx = X4;
+
+/// The X5 (T0) register.
+///
+/// Temporary register in the RISC-V ABI.
+pub const X5: regidx = regidx::Regidx(BitVector::new(5));
+
+/// The T0 (X5) register.
+///
+/// Temporary register in the RISC-V ABI.
+pub const T0: regidx = X5;
+
+/// The X6 (T1) register.
+///
+/// Temporary register in the RISC-V ABI.
+pub const X6: regidx = regidx::Regidx(BitVector::new(6));
+
+/// The T1 (X6) register.
+///
+/// Temporary register in the RISC-V ABI.
+pub const T1: regidx = X6;
+
+/// The X7 (T2) register.
+///
+/// Temporary register in the RISC-V ABI.
+pub const X7: regidx = regidx::Regidx(BitVector::new(7));
+
+/// The T2 (X7) register.
+///
+/// Temporary register in the RISC-V ABI.
+pub const T2: regidx = X7;
+
+/// The X8 (S0/FP) register.
+///
+/// Saved register 0 / Frame pointer in the RISC-V ABI.
+pub const X8: regidx = regidx::Regidx(BitVector::new(8));
+
+/// The S0 (X8) register.
+///
+/// Saved register 0 / Frame pointer in the RISC-V ABI.
+pub const S0: regidx = X8;
+
+/// The FP (X8) register.
+///
+/// Frame pointer in the RISC-V ABI.
+pub const FP: regidx = X8;
+
+/// The X9 (S1) register.
+///
+/// Saved register 1 in the RISC-V ABI.
+pub const X9: regidx = regidx::Regidx(BitVector::new(9));
+
+/// The S1 (X9) register.
+///
+/// Saved register 1 in the RISC-V ABI.
+pub const S1: regidx = X9;
+
+/// The X10 (A0) register.
+///
+/// Function argument / Return value in the RISC-V ABI.
+pub const X10: regidx = regidx::Regidx(BitVector::new(10));
+
+/// The A0 (X10) register.
+///
+/// Function argument / Return value in the RISC-V ABI.
+pub const A0: regidx = X10;
+
+/// The X11 (A1) register.
+///
+/// Function argument / Return value in the RISC-V ABI.
+pub const X11: regidx = regidx::Regidx(BitVector::new(11));
+
+/// The A1 (X11) register.
+///
+/// Function argument / Return value in the RISC-V ABI.
+pub const A1: regidx = X11;
+
+/// The X12 (A2) register.
+///
+/// Function argument in the RISC-V ABI.
+pub const X12: regidx = regidx::Regidx(BitVector::new(12));
+
+/// The A2 (X12) register.
+///
+/// Function argument in the RISC-V ABI.
+pub const A2: regidx = X12;
+
+/// The X13 (A3) register.
+///
+/// Function argument in the RISC-V ABI.
+pub const X13: regidx = regidx::Regidx(BitVector::new(13));
+
+/// The A3 (X13) register.
+///
+/// Function argument in the RISC-V ABI.
+pub const A3: regidx = X13;
+
+/// The X14 (A4) register.
+///
+/// Function argument in the RISC-V ABI.
+pub const X14: regidx = regidx::Regidx(BitVector::new(14));
+
+/// The A4 (X14) register.
+///
+/// Function argument in the RISC-V ABI.
+pub const A4: regidx = X14;
+
+/// The X15 (A5) register.
+///
+/// Function argument in the RISC-V ABI.
+pub const X15: regidx = regidx::Regidx(BitVector::new(15));
+
+/// The A5 (X15) register.
+///
+/// Function argument in the RISC-V ABI.
+pub const A5: regidx = X15;
+
+/// The X16 (A6) register.
+///
+/// Function argument in the RISC-V ABI.
+pub const X16: regidx = regidx::Regidx(BitVector::new(16));
+
+/// The A6 (X16) register.
+///
+/// Function argument in the RISC-V ABI.
+pub const A6: regidx = X16;
+
+/// The X17 (A7) register.
+///
+/// Function argument in the RISC-V ABI.
+pub const X17: regidx = regidx::Regidx(BitVector::new(17));
+
+/// The A7 (X17) register.
+///
+/// Function argument in the RISC-V ABI.
+pub const A7: regidx = X17;
+
+/// The X18 (S2) register.
+///
+/// Saved register 2 in the RISC-V ABI.
+pub const X18: regidx = regidx::Regidx(BitVector::new(18));
+
+/// The S2 (X18) register.
+///
+/// Saved register 2 in the RISC-V ABI.
+pub const S2: regidx = X18;
+
+/// The X19 (S3) register.
+///
+/// Saved register 3 in the RISC-V ABI.
+pub const X19: regidx = regidx::Regidx(BitVector::new(19));
+
+/// The S3 (X19) register.
+///
+/// Saved register 3 in the RISC-V ABI.
+pub const S3: regidx = X19;
+
+/// The X20 (S4) register.
+///
+/// Saved register 4 in the RISC-V ABI.
+pub const X20: regidx = regidx::Regidx(BitVector::new(20));
+
+/// The S4 (X20) register.
+///
+/// Saved register 4 in the RISC-V ABI.
+pub const S4: regidx = X20;
+
+/// The X21 (S5) register.
+///
+/// Saved register 5 in the RISC-V ABI.
+pub const X21: regidx = regidx::Regidx(BitVector::new(21));
+
+/// The S5 (X21) register.
+///
+/// Saved register 5 in the RISC-V ABI.
+pub const S5: regidx = X21;
+
+/// The X22 (S6) register.
+///
+/// Saved register 6 in the RISC-V ABI.
+pub const X22: regidx = regidx::Regidx(BitVector::new(22));
+
+/// The S6 (X22) register.
+///
+/// Saved register 6 in the RISC-V ABI.
+pub const S6: regidx = X22;
+
+/// The X23 (S7) register.
+///
+/// Saved register 7 in the RISC-V ABI.
+pub const X23: regidx = regidx::Regidx(BitVector::new(23));
+
+/// The S7 (X23) register.
+///
+/// Saved register 7 in the RISC-V ABI.
+pub const S7: regidx = X23;
+
+/// The X24 (S8) register.
+///
+/// Saved register 8 in the RISC-V ABI.
+pub const X24: regidx = regidx::Regidx(BitVector::new(24));
+
+/// The S8 (X24) register.
+///
+/// Saved register 8 in the RISC-V ABI.
+pub const S8: regidx = X24;
+
+/// The X25 (S9) register.
+///
+/// Saved register 9 in the RISC-V ABI.
+pub const X25: regidx = regidx::Regidx(BitVector::new(25));
+
+/// The S9 (X25) register.
+///
+/// Saved register 9 in the RISC-V ABI.
+pub const S9: regidx = X25;
+
+/// The X26 (S10) register.
+///
+/// Saved register 10 in the RISC-V ABI.
+pub const X26: regidx = regidx::Regidx(BitVector::new(26));
+
+/// The S10 (X26) register.
+///
+/// Saved register 10 in the RISC-V ABI.
+pub const S10: regidx = X26;
+
+/// The X27 (S11) register.
+///
+/// Saved register 11 in the RISC-V ABI.
+pub const X27: regidx = regidx::Regidx(BitVector::new(27));
+
+/// The S11 (X27) register.
+///
+/// Saved register 11 in the RISC-V ABI.
+pub const S11: regidx = X27;
+
+/// The X28 (T3) register.
+///
+/// Temporary register in the RISC-V ABI.
+pub const X28: regidx = regidx::Regidx(BitVector::new(28));
+
+/// The T3 (X28) register.
+///
+/// Temporary register in the RISC-V ABI.
+pub const T3: regidx = X28;
+
+/// The X29 (T4) register.
+///
+/// Temporary register in the RISC-V ABI.
+pub const X29: regidx = regidx::Regidx(BitVector::new(29));
+
+/// The T4 (X29) register.
+///
+/// Temporary register in the RISC-V ABI.
+pub const T4: regidx = X29;
+
+/// The X30 (T5) register.
+///
+/// Temporary register in the RISC-V ABI.
+pub const X30: regidx = regidx::Regidx(BitVector::new(30));
+
+/// The T5 (X30) register.
+///
+/// Temporary register in the RISC-V ABI.
+pub const T5: regidx = X30;
+
+/// The X31 (T6) register.
+///
+/// Temporary register in the RISC-V ABI.
+pub const X31: regidx = regidx::Regidx(BitVector::new(31));
+
+/// The T6 (X31) register.
+///
+/// Temporary register in the RISC-V ABI.
+pub const T6: regidx = X31;
+
+// ———————————————————————— Floating Point Registers ———————————————————————— //
+
+/// The type of floating point registers
+pub type FloatRegister = fregidx;
+
+/// The F0 (FT0) register.
+///
+/// Temporary register 0 in the RISC-V ABI.
+pub const F0: fregidx = fregidx::Fregidx(BitVector::new(0));
+
+/// The FT0 (F0) register.
+///
+/// Temporary register 0 in the RISC-V ABI.
+pub const FT0: fregidx = F0;
+
+/// The F1 (FT1) register.
+///
+/// Temporary register 1 in the RISC-V ABI.
+pub const F1: fregidx = fregidx::Fregidx(BitVector::new(1));
+
+/// The FT1 (F1) register.
+///
+/// Temporary register 1 in the RISC-V ABI.
+pub const FT1: fregidx = F1;
+
+/// The F2 (FT2) register.
+///
+/// Temporary register 2 in the RISC-V ABI.
+pub const F2: fregidx = fregidx::Fregidx(BitVector::new(2));
+
+/// The FT2 (F2) register.
+///
+/// Temporary register 2 in the RISC-V ABI.
+pub const FT2: fregidx = F2;
+
+/// The F3 (FT3) register.
+///
+/// Temporary register 3 in the RISC-V ABI.
+pub const F3: fregidx = fregidx::Fregidx(BitVector::new(3));
+
+/// The FT3 (F3) register.
+///
+/// Temporary register 3 in the RISC-V ABI.
+pub const FT3: fregidx = F3;
+
+/// The F4 (FT4) register.
+///
+/// Temporary register 4 in the RISC-V ABI.
+pub const F4: fregidx = fregidx::Fregidx(BitVector::new(4));
+
+/// The FT4 (F4) register.
+///
+/// Temporary register 4 in the RISC-V ABI.
+pub const FT4: fregidx = F4;
+
+/// The F5 (FT5) register.
+///
+/// Temporary register 5 in the RISC-V ABI.
+pub const F5: fregidx = fregidx::Fregidx(BitVector::new(5));
+
+/// The FT5 (F5) register.
+///
+/// Temporary register 5 in the RISC-V ABI.
+pub const FT5: fregidx = F5;
+
+/// The F6 (FT6) register.
+///
+/// Temporary register 6 in the RISC-V ABI.
+pub const F6: fregidx = fregidx::Fregidx(BitVector::new(6));
+
+/// The FT6 (F6) register.
+///
+/// Temporary register 6 in the RISC-V ABI.
+pub const FT6: fregidx = F6;
+
+/// The F7 (FT7) register.
+///
+/// Temporary register 7 in the RISC-V ABI.
+pub const F7: fregidx = fregidx::Fregidx(BitVector::new(7));
+
+/// The FT7 (F7) register.
+///
+/// Temporary register 7 in the RISC-V ABI.
+pub const FT7: fregidx = F7;
+
+/// The F8 (FS0) register.
+///
+/// Saved register 0 in the RISC-V ABI.
+pub const F8: fregidx = fregidx::Fregidx(BitVector::new(8));
+
+/// The FS0 (F8) register.
+///
+/// Saved register 0 in the RISC-V ABI.
+pub const FS0: fregidx = F8;
+
+/// The F9 (FS1) register.
+///
+/// Saved register 1 in the RISC-V ABI.
+pub const F9: fregidx = fregidx::Fregidx(BitVector::new(9));
+
+/// The FS1 (F9) register.
+///
+/// Saved register 1 in the RISC-V ABI.
+pub const FS1: fregidx = F9;
+
+/// The F10 (FA0) register.
+///
+/// Function argument / Return value in the RISC-V ABI.
+pub const F10: fregidx = fregidx::Fregidx(BitVector::new(10));
+
+/// The FA0 (F10) register.
+///
+/// Function argument / Return value in the RISC-V ABI.
+pub const FA0: fregidx = F10;
+
+/// The F11 (FA1) register.
+///
+/// Function argument / Return value in the RISC-V ABI.
+pub const F11: fregidx = fregidx::Fregidx(BitVector::new(11));
+
+/// The FA1 (F11) register.
+///
+/// Function argument / Return value in the RISC-V ABI.
+pub const FA1: fregidx = F11;
+
+/// The F12 (FA2) register.
+///
+/// Function argument in the RISC-V ABI.
+pub const F12: fregidx = fregidx::Fregidx(BitVector::new(12));
+
+/// The FA2 (F12) register.
+///
+/// Function argument in the RISC-V ABI.
+pub const FA2: fregidx = F12;
+
+/// The F13 (FA3) register.
+///
+/// Function argument in the RISC-V ABI.
+pub const F13: fregidx = fregidx::Fregidx(BitVector::new(13));
+
+/// The FA3 (F13) register.
+///
+/// Function argument in the RISC-V ABI.
+pub const FA3: fregidx = F13;
+
+/// The F14 (FA4) register.
+///
+/// Function argument in the RISC-V ABI.
+pub const F14: fregidx = fregidx::Fregidx(BitVector::new(14));
+
+/// The FA4 (F14) register.
+///
+/// Function argument in the RISC-V ABI.
+pub const FA4: fregidx = F14;
+
+/// The F15 (FA5) register.
+///
+/// Function argument in the RISC-V ABI.
+pub const F15: fregidx = fregidx::Fregidx(BitVector::new(15));
+
+/// The FA5 (F15) register.
+///
+/// Function argument in the RISC-V ABI.
+pub const FA5: fregidx = F15;
+
+/// The F16 (FA6) register.
+///
+/// Function argument in the RISC-V ABI.
+pub const F16: fregidx = fregidx::Fregidx(BitVector::new(16));
+
+/// The FA6 (F16) register.
+///
+/// Function argument in the RISC-V ABI.
+pub const FA6: fregidx = F16;
+
+/// The F17 (FA7) register.
+///
+/// Function argument in the RISC-V ABI.
+pub const F17: fregidx = fregidx::Fregidx(BitVector::new(17));
+
+/// The FA7 (F17) register.
+///
+/// Function argument in the RISC-V ABI.
+pub const FA7: fregidx = F17;
+
+/// The F18 (FS2) register.
+///
+/// Saved register 2 in the RISC-V ABI.
+pub const F18: fregidx = fregidx::Fregidx(BitVector::new(18));
+
+/// The FS2 (F18) register.
+///
+/// Saved register 2 in the RISC-V ABI.
+pub const FS2: fregidx = F18;
+
+/// The F19 (FS3) register.
+///
+/// Saved register 3 in the RISC-V ABI.
+pub const F19: fregidx = fregidx::Fregidx(BitVector::new(19));
+
+/// The FS3 (F19) register.
+///
+/// Saved register 3 in the RISC-V ABI.
+pub const FS3: fregidx = F19;
+
+/// The F20 (FS4) register.
+///
+/// Saved register 4 in the RISC-V ABI.
+pub const F20: fregidx = fregidx::Fregidx(BitVector::new(20));
+
+/// The FS4 (F20) register.
+///
+/// Saved register 4 in the RISC-V ABI.
+pub const FS4: fregidx = F20;
+
+/// The F21 (FS5) register.
+///
+/// Saved register 5 in the RISC-V ABI.
+pub const F21: fregidx = fregidx::Fregidx(BitVector::new(21));
+
+/// The FS5 (F21) register.
+///
+/// Saved register 5 in the RISC-V ABI.
+pub const FS5: fregidx = F21;
+
+/// The F22 (FS6) register.
+///
+/// Saved register 6 in the RISC-V ABI.
+pub const F22: fregidx = fregidx::Fregidx(BitVector::new(22));
+
+/// The FS6 (F22) register.
+///
+/// Saved register 6 in the RISC-V ABI.
+pub const FS6: fregidx = F22;
+
+/// The F23 (FS7) register.
+///
+/// Saved register 7 in the RISC-V ABI.
+pub const F23: fregidx = fregidx::Fregidx(BitVector::new(23));
+
+/// The FS7 (F23) register.
+///
+/// Saved register 7 in the RISC-V ABI.
+pub const FS7: fregidx = F23;
+
+/// The F24 (FS8) register.
+///
+/// Saved register 8 in the RISC-V ABI.
+pub const F24: fregidx = fregidx::Fregidx(BitVector::new(24));
+
+/// The FS8 (F24) register.
+///
+/// Saved register 8 in the RISC-V ABI.
+pub const FS8: fregidx = F24;
+
+/// The F25 (FS9) register.
+///
+/// Saved register 9 in the RISC-V ABI.
+pub const F25: fregidx = fregidx::Fregidx(BitVector::new(25));
+
+/// The FS9 (F25) register.
+///
+/// Saved register 9 in the RISC-V ABI.
+pub const FS9: fregidx = F25;
+
+/// The F26 (FS10) register.
+///
+/// Saved register 10 in the RISC-V ABI.
+pub const F26: fregidx = fregidx::Fregidx(BitVector::new(26));
+
+/// The FS10 (F26) register.
+///
+/// Saved register 10 in the RISC-V ABI.
+pub const FS10: fregidx = F26;
+
+/// The F27 (FS11) register.
+///
+/// Saved register 11 in the RISC-V ABI.
+pub const F27: fregidx = fregidx::Fregidx(BitVector::new(27));
+
+/// The FS11 (F27) register.
+///
+/// Saved register 11 in the RISC-V ABI.
+pub const FS11: fregidx = F27;
+
+/// The F28 (FT8) register.
+///
+/// Temporary register 8 in the RISC-V ABI.
+pub const F28: fregidx = fregidx::Fregidx(BitVector::new(28));
+
+/// The FT8 (F28) register.
+///
+/// Temporary register 8 in the RISC-V ABI.
+pub const FT8: fregidx = F28;
+
+/// The F29 (FT9) register.
+///
+/// Temporary register 9 in the RISC-V ABI.
+pub const F29: fregidx = fregidx::Fregidx(BitVector::new(29));
+
+/// The FT9 (F29) register.
+///
+/// Temporary register 9 in the RISC-V ABI.
+pub const FT9: fregidx = F29;
+
+/// The F30 (FT10) register.
+///
+/// Temporary register 10 in the RISC-V ABI.
+pub const F30: fregidx = fregidx::Fregidx(BitVector::new(30));
+
+/// The FT10 (F30) register.
+///
+/// Temporary register 10 in the RISC-V ABI.
+pub const FT10: fregidx = F30;
+
+/// The F31 (FT11) register.
+///
+/// Temporary register 11 in the RISC-V ABI.
+pub const F31: fregidx = fregidx::Fregidx(BitVector::new(31));
+
+/// The FT11 (F31) register.
+///
+/// Temporary register 11 in the RISC-V ABI.
+pub const FT11: fregidx = F31;
+
+// ———————————————————————————— Vector Registers ———————————————————————————— //
+
+/// The type of vector registers
+pub type VectorRegister = vregidx;
+
+/// The V0 register.
+///
+/// Holds the mask of masked vector instructions.
+pub const V0: vregidx = vregidx::Vregidx(BitVector::new(0));
+
+/// The V1 register.
+pub const V1: vregidx = vregidx::Vregidx(BitVector::new(1));
+
+/// The V2 register.
+pub const V2: vregidx = vregidx::Vregidx(BitVector::new(2));
+
+/// The V3 register.
+pub const V3: vregidx = vregidx::Vregidx(BitVector::new(3));
+
+/// The V4 register.
+pub const V4: vregidx = vregidx::Vregidx(BitVector::new(4));
+
+/// The V5 register.
+pub const V5: vregidx = vregidx::Vregidx(BitVector::new(5));
+
+/// The V6 register.
+pub const V6: vregidx = vregidx::Vregidx(BitVector::new(6));
+
+/// The V7 register.
+pub const V7: vregidx = vregidx::Vregidx(BitVector::new(7));
+
+/// The V8 register.
+pub const V8: vregidx = vregidx::Vregidx(BitVector::new(8));
+
+/// The V9 register.
+pub const V9: vregidx = vregidx::Vregidx(BitVector::new(9));
+
+/// The V10 register.
+pub const V10: vregidx = vregidx::Vregidx(BitVector::new(10));
+
+/// The V11 register.
+pub const V11: vregidx = vregidx::Vregidx(BitVector::new(11));
+
+/// The V12 register.
+pub const V12: vregidx = vregidx::Vregidx(BitVector::new(12));
+
+/// The V13 register.
+pub const V13: vregidx = vregidx::Vregidx(BitVector::new(13));
+
+/// The V14 register.
+pub const V14: vregidx = vregidx::Vregidx(BitVector::new(14));
+
+/// The V15 register.
+pub const V15: vregidx = vregidx::Vregidx(BitVector::new(15));
+
+/// The V16 register.
+pub const V16: vregidx = vregidx::Vregidx(BitVector::new(16));
+
+/// The V17 register.
+pub const V17: vregidx = vregidx::Vregidx(BitVector::new(17));
+
+/// The V18 register.
+pub const V18: vregidx = vregidx::Vregidx(BitVector::new(18));
+
+/// The V19 register.
+pub const V19: vregidx = vregidx::Vregidx(BitVector::new(19));
+
+/// The V20 register.
+pub const V20: vregidx = vregidx::Vregidx(BitVector::new(20));
+
+/// The V21 register.
+pub const V21: vregidx = vregidx::Vregidx(BitVector::new(21));
+
+/// The V22 register.
+pub const V22: vregidx = vregidx::Vregidx(BitVector::new(22));
+
+/// The V23 register.
+pub const V23: vregidx = vregidx::Vregidx(BitVector::new(23));
+
+/// The V24 register.
+pub const V24: vregidx = vregidx::Vregidx(BitVector::new(24));
+
+/// The V25 register.
+pub const V25: vregidx = vregidx::Vregidx(BitVector::new(25));
+
+/// The V26 register.
+pub const V26: vregidx = vregidx::Vregidx(BitVector::new(26));
+
+/// The V27 register.
+pub const V27: vregidx = vregidx::Vregidx(BitVector::new(27));
+
+/// The V28 register.
+pub const V28: vregidx = vregidx::Vregidx(BitVector::new(28));
+
+/// The V29 register.
+pub const V29: vregidx = vregidx::Vregidx(BitVector::new(29));
+
+/// The V30 register.
+pub const V30: vregidx = vregidx::Vregidx(BitVector::new(30));
+
+/// The V31 register.
+pub const V31: vregidx = vregidx::Vregidx(BitVector::new(31));
+
+// —————————————————————— Control and Status Registers —————————————————————— //
+
+/// Declares the [Csr] enum, CSR names are given by the `csr_name_map` mapping of the
+/// specification.
+macro_rules! csrs {
+    (
+        single {
+            $($(#[$doc:meta])* $name:ident = $addr:literal;)*
+        }
+        indexed {
+            $($(#[$idoc:meta])* $iname:ident($min:literal..=$max:literal) = $base:literal;)*
+        }
+    ) => {
+        /// A Control and Status Register (CSR).
+        ///
+        /// Indexed CSRs (such as `pmpcfgN`) hold the index of the register, which must be within
+        /// the range defined by the specification.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum Csr {
+            $($(#[$doc])* $name,)*
+            $($(#[$idoc])* $iname(u8),)*
+        }
+
+        impl Csr {
+            /// Returns the CSR at the given address, if any.
+            pub const fn from_address(address: u64) -> Option<Csr> {
+                match address {
+                    $($addr => Some(Csr::$name),)*
+                    $(_ if address.wrapping_sub($base + $min) <= $max - $min => {
+                        Some(Csr::$iname((address - $base) as u8))
+                    })*
+                    _ => None,
+                }
+            }
+
+            /// Returns the address of the CSR.
+            ///
+            /// Panics if the index of an indexed CSR is out of range.
+            pub const fn address(self) -> u64 {
+                match self {
+                    $(Csr::$name => $addr,)*
+                    $(Csr::$iname(idx) => {
+                        assert!(idx.wrapping_sub($min) <= $max - $min, "CSR index out of range");
+                        $base + idx as u64
+                    })*
+                }
+            }
+        }
+    };
+}
+
+csrs! {
+    single {
+        /// Floating-point accrued exceptions.
+        Fflags = 0x001;
+        /// Floating-point dynamic rounding mode.
+        Frm = 0x002;
+        /// Floating-point control and status register.
+        Fcsr = 0x003;
+        /// Vector start position.
+        Vstart = 0x008;
+        /// Vector fixed-point saturation flag.
+        Vxsat = 0x009;
+        /// Vector fixed-point rounding mode.
+        Vxrm = 0x00A;
+        /// Vector control and status register.
+        Vcsr = 0x00F;
+        /// Entropy source.
+        Seed = 0x015;
+        /// Vector length.
+        Vl = 0xC20;
+        /// Vector data type.
+        Vtype = 0xC21;
+        /// Vector register length in bytes.
+        Vlenb = 0xC22;
+        /// Cycle counter.
+        Cycle = 0xC00;
+        /// Timer.
+        Time = 0xC01;
+        /// Instructions retired counter.
+        Instret = 0xC02;
+        /// Upper 32 bits of `cycle`, RV32 only.
+        Cycleh = 0xC80;
+        /// Upper 32 bits of `time`, RV32 only.
+        Timeh = 0xC81;
+        /// Upper 32 bits of `instret`, RV32 only.
+        Instreth = 0xC82;
+        /// Supervisor status register.
+        Sstatus = 0x100;
+        /// Supervisor interrupt-enable register.
+        Sie = 0x104;
+        /// Supervisor trap handler base address.
+        Stvec = 0x105;
+        /// Supervisor counter enable.
+        Scounteren = 0x106;
+        /// Supervisor environment configuration register.
+        Senvcfg = 0x10A;
+        /// Supervisor scratch register.
+        Sscratch = 0x140;
+        /// Supervisor exception program counter.
+        Sepc = 0x141;
+        /// Supervisor trap cause.
+        Scause = 0x142;
+        /// Supervisor trap value.
+        Stval = 0x143;
+        /// Supervisor interrupt pending.
+        Sip = 0x144;
+        /// Supervisor timer compare.
+        Stimecmp = 0x14D;
+        /// Upper 32 bits of `stimecmp`, RV32 only.
+        Stimecmph = 0x15D;
+        /// Supervisor address translation and protection.
+        Satp = 0x180;
+        /// Supervisor count overflow.
+        Scountovf = 0xDA0;
+        /// Vendor ID.
+        Mvendorid = 0xF11;
+        /// Architecture ID.
+        Marchid = 0xF12;
+        /// Implementation ID.
+        Mimpid = 0xF13;
+        /// Hardware thread ID.
+        Mhartid = 0xF14;
+        /// Pointer to configuration data structure.
+        Mconfigptr = 0xF15;
+        /// Machine status register.
+        Mstatus = 0x300;
+        /// ISA and extensions.
+        Misa = 0x301;
+        /// Machine exception delegation register.
+        Medeleg = 0x302;
+        /// Machine interrupt delegation register.
+        Mideleg = 0x303;
+        /// Machine interrupt-enable register.
+        Mie = 0x304;
+        /// Machine trap handler base address.
+        Mtvec = 0x305;
+        /// Machine counter enable.
+        Mcounteren = 0x306;
+        /// Machine environment configuration register.
+        Menvcfg = 0x30A;
+        /// Upper 32 bits of `mstatus`, RV32 only.
+        Mstatush = 0x310;
+        /// Upper 32 bits of `medeleg`, RV32 only.
+        Medelegh = 0x312;
+        /// Upper 32 bits of `menvcfg`, RV32 only.
+        Menvcfgh = 0x31A;
+        /// Machine counter-inhibit register.
+        Mcountinhibit = 0x320;
+        /// Machine cycle counter configuration.
+        Mcyclecfg = 0x321;
+        /// Machine instret counter configuration.
+        Minstretcfg = 0x322;
+        /// Upper 32 bits of `mcyclecfg`, RV32 only.
+        Mcyclecfgh = 0x721;
+        /// Upper 32 bits of `minstretcfg`, RV32 only.
+        Minstretcfgh = 0x722;
+        /// Machine scratch register.
+        Mscratch = 0x340;
+        /// Machine exception program counter.
+        Mepc = 0x341;
+        /// Machine trap cause.
+        Mcause = 0x342;
+        /// Machine trap value.
+        Mtval = 0x343;
+        /// Machine interrupt pending.
+        Mip = 0x344;
+        /// Machine security configuration.
+        Mseccfg = 0x747;
+        /// Upper 32 bits of `mseccfg`, RV32 only.
+        Mseccfgh = 0x757;
+        /// Machine cycle counter.
+        Mcycle = 0xB00;
+        /// Machine instructions retired counter.
+        Minstret = 0xB02;
+        /// Upper 32 bits of `mcycle`, RV32 only.
+        Mcycleh = 0xB80;
+        /// Upper 32 bits of `minstret`, RV32 only.
+        Minstreth = 0xB82;
+        /// Debug/Trace trigger register select.
+        Tselect = 0x7A0;
+        /// First Debug/Trace trigger data register.
+        Tdata1 = 0x7A1;
+        /// Second Debug/Trace trigger data register.
+        Tdata2 = 0x7A2;
+        /// Third Debug/Trace trigger data register.
+        Tdata3 = 0x7A3;
+    }
+    indexed {
+        /// Performance-monitoring counters 3 to 31.
+        Hpmcounter(3..=31) = 0xC00;
+        /// Upper 32 bits of the performance-monitoring counters 3 to 31, RV32 only.
+        Hpmcounterh(3..=31) = 0xC80;
+        /// Physical memory protection configuration registers 0 to 15.
+        Pmpcfg(0..=15) = 0x3A0;
+        /// Physical memory protection address registers 0 to 63.
+        Pmpaddr(0..=63) = 0x3B0;
+        /// Machine performance-monitoring counters 3 to 31.
+        Mhpmcounter(3..=31) = 0xB00;
+        /// Upper 32 bits of the machine performance-monitoring counters 3 to 31, RV32 only.
+        Mhpmcounterh(3..=31) = 0xB80;
+        /// Machine performance-monitoring event selectors 3 to 31.
+        Mhpmevent(3..=31) = 0x320;
+        /// Upper 32 bits of the machine performance-monitoring event selectors 3 to 31, RV32 only.
+        Mhpmeventh(3..=31) = 0x720;
+    }
+}
+
+impl Csr {
+    /// Returns the CSR with the given name, if any.
+    pub fn from_name(name: &str) -> Option<Csr> {
+        static NAMES: OnceLock<HashMap<String, Csr>> = OnceLock::new();
+        let names = NAMES.get_or_init(|| {
+            (0..(1 << 12))
+                .filter_map(Csr::from_address)
+                .map(|csr| (csr.to_string(), csr))
+                .collect()
+        });
+        names.get(name).copied()
+    }
+}
+
+impl fmt::Display for Csr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&raw::csr_name_map_forwards(bv(self.address())))
+    }
+}
+
+impl FromStr for Csr {
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Csr::from_name(name).ok_or(())
+    }
+}
+
+// ———————————————————————————————— CSR Views ——————————————————————————————— //
+
+/// Extracts the field of `width` bits starting at `lsb`.
+const fn field(bits: u64, lsb: u64, width: u64) -> u64 {
+    (bits >> lsb) & (u64::MAX >> (64 - width))
+}
+
+/// Typed view of the `mstatus` register, also used for `sstatus`.
+impl raw::Mstatus {
+    /// The `SIE` field.
+    pub const fn sie(self) -> bool {
+        field(self.bits.bits(), 1, 1) != 0
+    }
+
+    /// The `MIE` field.
+    pub const fn mie(self) -> bool {
+        field(self.bits.bits(), 3, 1) != 0
+    }
+
+    /// The `SPIE` field.
+    pub const fn spie(self) -> bool {
+        field(self.bits.bits(), 5, 1) != 0
+    }
+
+    /// The `MPIE` field.
+    pub const fn mpie(self) -> bool {
+        field(self.bits.bits(), 7, 1) != 0
+    }
+
+    /// The `SPP` field.
+    pub const fn spp(self) -> Privilege {
+        match field(self.bits.bits(), 8, 1) {
+            0 => Privilege::User,
+            _ => Privilege::Supervisor,
+        }
+    }
+
+    /// The `MPP` field.
+    pub const fn mpp(self) -> Privilege {
+        match field(self.bits.bits(), 11, 2) {
+            0b00 => Privilege::User,
+            0b01 => Privilege::Supervisor,
+            _ => Privilege::Machine,
+        }
+    }
+
+    /// The `VS` field.
+    pub const fn vs(self) -> u8 {
+        field(self.bits.bits(), 9, 2) as u8
+    }
+
+    /// The `FS` field.
+    pub const fn fs(self) -> u8 {
+        field(self.bits.bits(), 13, 2) as u8
+    }
+
+    /// The `XS` field.
+    pub const fn xs(self) -> u8 {
+        field(self.bits.bits(), 15, 2) as u8
+    }
+
+    /// The `MPRV` field.
+    pub const fn mprv(self) -> bool {
+        field(self.bits.bits(), 17, 1) != 0
+    }
+
+    /// The `SUM` field.
+    pub const fn sum(self) -> bool {
+        field(self.bits.bits(), 18, 1) != 0
+    }
+
+    /// The `MXR` field.
+    pub const fn mxr(self) -> bool {
+        field(self.bits.bits(), 19, 1) != 0
+    }
+
+    /// The `TVM` field.
+    pub const fn tvm(self) -> bool {
+        field(self.bits.bits(), 20, 1) != 0
+    }
+
+    /// The `TW` field.
+    pub const fn tw(self) -> bool {
+        field(self.bits.bits(), 21, 1) != 0
+    }
+
+    /// The `TSR` field.
+    pub const fn tsr(self) -> bool {
+        field(self.bits.bits(), 22, 1) != 0
+    }
+
+    /// The `SD` field.
+    pub const fn sd(self) -> bool {
+        field(self.bits.bits(), 63, 1) != 0
+    }
+}
+
+/// Typed view of the interrupt registers: `mie`, `mip`, `mideleg`, `sie` and `sip`.
+impl raw::Minterrupts {
+    /// The supervisor software interrupt bit.
+    pub const fn ssi(self) -> bool {
+        field(self.bits.bits(), 1, 1) != 0
+    }
+
+    /// The machine software interrupt bit.
+    pub const fn msi(self) -> bool {
+        field(self.bits.bits(), 3, 1) != 0
+    }
+
+    /// The supervisor timer interrupt bit.
+    pub const fn sti(self) -> bool {
+        field(self.bits.bits(), 5, 1) != 0
+    }
+
+    /// The machine timer interrupt bit.
+    pub const fn mti(self) -> bool {
+        field(self.bits.bits(), 7, 1) != 0
+    }
+
+    /// The supervisor external interrupt bit.
+    pub const fn sei(self) -> bool {
+        field(self.bits.bits(), 9, 1) != 0
+    }
+
+    /// The machine external interrupt bit.
+    pub const fn mei(self) -> bool {
+        field(self.bits.bits(), 11, 1) != 0
+    }
+
+    /// The local counter overflow interrupt bit.
+    pub const fn lcofi(self) -> bool {
+        field(self.bits.bits(), 13, 1) != 0
+    }
+}
+
+/// Typed view of the trap vector registers: `mtvec` and `stvec`.
+impl raw::Mtvec {
+    /// The `MODE` field.
+    pub const fn mode(self) -> u8 {
+        field(self.bits.bits(), 0, 2) as u8
+    }
+
+    /// The `BASE` address.
+    pub const fn base(self) -> u64 {
+        field(self.bits.bits(), 2, 62) << 2
+    }
+}
+
+/// Typed view of the trap cause registers: `mcause` and `scause`.
+impl raw::Mcause {
+    /// The interrupt bit.
+    pub const fn interrupt(self) -> bool {
+        field(self.bits.bits(), 63, 1) != 0
+    }
+
+    /// The exception code.
+    pub const fn code(self) -> u64 {
+        field(self.bits.bits(), 0, 63)
+    }
+}
+
+/// Typed view of the `satp` register.
+impl raw::Satp64 {
+    /// The `MODE` field.
+    pub const fn mode(self) -> u8 {
+        field(self.bits.bits(), 60, 4) as u8
+    }
+
+    /// The `ASID` field.
+    pub const fn asid(self) -> u16 {
+        field(self.bits.bits(), 44, 16) as u16
+    }
+
+    /// The `PPN` field.
+    pub const fn ppn(self) -> u64 {
+        field(self.bits.bits(), 0, 44)
+    }
+}
+
+/// Typed view of the counter enable registers: `mcounteren` and `scounteren`.
+impl raw::Counteren {
+    /// The `CY` bit.
+    pub const fn cy(self) -> bool {
+        field(self.bits.bits(), 0, 1) != 0
+    }
+
+    /// The `TM` bit.
+    pub const fn tm(self) -> bool {
+        field(self.bits.bits(), 1, 1) != 0
+    }
+
+    /// The `IR` bit.
+    pub const fn ir(self) -> bool {
+        field(self.bits.bits(), 2, 1) != 0
+    }
+
+    /// The `HPMn` bit, for `n` in 3 to 31.
+    pub const fn hpm(self, n: u8) -> bool {
+        assert!(3 <= n && n <= 31, "Invalid performance counter");
+        field(self.bits.bits(), n as u64, 1) != 0
+    }
+}
+
+/// Typed view of the `mcountinhibit` register.
+impl raw::Counterin {
+    /// The `CY` bit.
+    pub const fn cy(self) -> bool {
+        field(self.bits.bits(), 0, 1) != 0
+    }
+
+    /// The `IR` bit.
+    pub const fn ir(self) -> bool {
+        field(self.bits.bits(), 2, 1) != 0
+    }
+
+    /// The `HPMn` bit, for `n` in 3 to 31.
+    pub const fn hpm(self, n: u8) -> bool {
+        assert!(3 <= n && n <= 31, "Invalid performance counter");
+        field(self.bits.bits(), n as u64, 1) != 0
+    }
+}
+
+/// Typed view of the `fcsr` register.
+impl raw::Fcsr {
+    /// The dynamic rounding mode.
+    pub const fn frm(self) -> u8 {
+        field(self.bits.bits(), 5, 3) as u8
+    }
+
+    /// The accrued exceptions.
+    pub const fn fflags(self) -> u8 {
+        field(self.bits.bits(), 0, 5) as u8
+    }
+}
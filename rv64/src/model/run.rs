@@ -0,0 +1,140 @@
+//! Run loop
+//!
+//! [Core::run_until] steps a core until one of the conditions of a [StopCondition] is met, or
+//! until a maximum number of steps has been executed.
+
+use super::HtifStatus;
+use super::raw::{self, Core, Privilege};
+
+type Predicate<'a> = Box<dyn FnMut(&mut Core) -> bool + 'a>;
+
+/// The conditions under which [Core::run_until] stops.
+///
+/// Conditions are checked after each step, in the order of the [StopReason] variants. By default
+/// no condition is set, and the core runs until the maximum number of steps is reached.
+#[derive(Default)]
+pub struct StopCondition<'a> {
+    breakpoints: Vec<u64>,
+    htif_exit: bool,
+    wfi: bool,
+    trap: Option<Privilege>,
+    predicate: Option<Predicate<'a>>,
+}
+
+/// The reason why [Core::run_until] stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StopReason {
+    /// The program signaled its completion through HTIF.
+    HtifExit(HtifStatus),
+    /// A trap has been taken to the given privilege mode.
+    Trap(Privilege),
+    /// The hart is waiting for an interrupt, but none is pending.
+    Wfi,
+    /// The PC reached a breakpoint, the instruction at the breakpoint has not been executed.
+    Breakpoint(u64),
+    /// The user-provided predicate returned true.
+    Predicate,
+    /// The maximum number of steps has been reached.
+    MaxSteps,
+}
+
+impl<'a> StopCondition<'a> {
+    /// Returns an empty stop condition, stopping only after the maximum number of steps.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop before executing the instruction at `pc`.
+    pub fn breakpoint(mut self, pc: u64) -> Self {
+        self.breakpoints.push(pc);
+        self
+    }
+
+    /// Stop when the program signals its completion through HTIF, see [Core::htif_status].
+    pub fn htif_exit(mut self) -> Self {
+        self.htif_exit = true;
+        self
+    }
+
+    /// Stop when the hart waits for an interrupt (e.g. after a `WFI`) and none is pending.
+    pub fn wfi(mut self) -> Self {
+        self.wfi = true;
+        self
+    }
+
+    /// Stop after a trap (exception or interrupt) is taken to the given privilege mode.
+    pub fn trap(mut self, privilege: Privilege) -> Self {
+        self.trap = Some(privilege);
+        self
+    }
+
+    /// Stop when `predicate` returns true.
+    pub fn when(mut self, predicate: impl FnMut(&mut Core) -> bool + 'a) -> Self {
+        self.predicate = Some(Box::new(predicate));
+        self
+    }
+}
+
+impl Core {
+    /// Step the core until a stop condition is met, or for at most `max_steps` steps.
+    ///
+    /// Returns the reason why the core stopped, and the number of instructions retired. Note that
+    /// the instruction at the initial PC is always executed, even if it is a breakpoint.
+    pub fn run_until(&mut self, mut cond: StopCondition, max_steps: u64) -> (StopReason, u64) {
+        let mut retired = 0;
+        for _ in 0..max_steps {
+            let step = self.step();
+            let trapped = has_trapped(&step);
+            if matches!(step, raw::Step::Step_Execute(_)) && !trapped {
+                retired += 1;
+            }
+
+            if cond.htif_exit && self.htif_done {
+                return (StopReason::HtifExit(self.htif_status()), retired);
+            }
+            if trapped && cond.trap == Some(self.cur_privilege) {
+                return (StopReason::Trap(self.cur_privilege), retired);
+            }
+            if cond.wfi && self.is_waiting() && !self.has_pending_interrupt() {
+                return (StopReason::Wfi, retired);
+            }
+            if cond.breakpoints.contains(&self.PC.bits()) {
+                return (StopReason::Breakpoint(self.PC.bits()), retired);
+            }
+            if let Some(predicate) = &mut cond.predicate
+                && predicate(self)
+            {
+                return (StopReason::Predicate, retired);
+            }
+        }
+        (StopReason::MaxSteps, retired)
+    }
+
+    fn is_waiting(&self) -> bool {
+        matches!(self.hart_state, raw::HartState::HART_WAITING(_))
+    }
+
+    /// Return true if an interrupt is both pending and enabled, which wakes up a waiting hart.
+    fn has_pending_interrupt(&self) -> bool {
+        (self.mip.bits & self.mie.bits).bits() != 0
+    }
+}
+
+/// Returns true if a trap has been taken during the step.
+pub(super) fn has_trapped(step: &raw::Step) -> bool {
+    match step {
+        raw::Step::Step_Pending_Interrupt(_)
+        | raw::Step::Step_Ext_Fetch_Failure(_)
+        | raw::Step::Step_Fetch_Failure(_) => true,
+        raw::Step::Step_Waiting(_) => false,
+        raw::Step::Step_Execute((result, _)) => match result {
+            raw::ExecutionResult::Retire_Success(())
+            | raw::ExecutionResult::Wait_For_Interrupt(()) => false,
+            // xRET instructions are reported as traps, but retire as regular instructions.
+            raw::ExecutionResult::Trap((_, ctl, _)) => {
+                matches!(ctl, raw::ctl_result::CTL_TRAP(_))
+            }
+            _ => true,
+        },
+    }
+}
@@ -0,0 +1,247 @@
+//! Snapshots of the architectural state
+//!
+//! A [CoreState] captures all the registers of a core: general purpose, floating point and vector
+//! registers, CSRs, PMP entries, the TLB, and the HTIF device state. Snapshots can be restored
+//! with [Core::restore], and compared with [diff] to check which parts of the state have been
+//! modified by an instruction or a trap.
+
+use super::raw::Core;
+use std::fmt;
+
+/// A snapshot of the architectural state of a core, see [Core::snapshot].
+///
+/// The physical memory attached to the core is not part of the snapshot.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(
+        softcore_prelude::serde::Serialize,
+        softcore_prelude::serde::Deserialize
+    ),
+    serde(crate = "softcore_prelude::serde")
+)]
+pub struct CoreState {
+    pub(super) core: Core,
+}
+
+impl CoreState {
+    /// Copy the architectural state of a core, leaving out the physical memory and platform.
+    pub(super) fn new(core: &Core) -> Self {
+        macro_rules! copy {
+            ($($field:ident),* $(,)?) => {
+                Core {
+                    $($field: core.$field.clone(),)*
+                    mem: Default::default(),
+                    platform: Default::default(),
+                }
+            };
+        }
+
+        #[rustfmt::skip]
+        let core = copy!(
+            PC, nextPC, x1, x2, x3, x4, x5, x6, x7, x8, x9, x10, x11, x12, x13, x14,
+            x15, x16, x17, x18, x19, x20, x21, x22, x23, x24, x25, x26, x27, x28, x29,
+            x30, x31, cur_privilege, cur_inst, misa, mstatus, menvcfg, senvcfg, mie,
+            mip, medeleg, mideleg, mtvec, mcause, mepc, mtval, mscratch, scounteren,
+            mcounteren, mcountinhibit, mcycle, mtime, minstret, minstret_increment,
+            mvendorid, mimpid, marchid, mhartid, mconfigptr, stvec, sscratch, sepc,
+            scause, stval, tselect, vstart, vl, vtype, pmpcfg_n, pmpaddr_n, vr0, vr1,
+            vr2, vr3, vr4, vr5, vr6, vr7, vr8, vr9, vr10, vr11, vr12, vr13, vr14, vr15,
+            vr16, vr17, vr18, vr19, vr20, vr21, vr22, vr23, vr24, vr25, vr26, vr27,
+            vr28, vr29, vr30, vr31, vcsr, mhpmevent, mhpmcounter, float_result,
+            float_fflags, f0, f1, f2, f3, f4, f5, f6, f7, f8, f9, f10, f11, f12, f13,
+            f14, f15, f16, f17, f18, f19, f20, f21, f22, f23, f24, f25, f26, f27, f28,
+            f29, f30, f31, fcsr, mcyclecfg, minstretcfg, mtimecmp, stimecmp,
+            htif_tohost, htif_done, htif_exit_code, htif_cmd_write, htif_payload_writes,
+            tlb, satp, hart_state, config,
+        );
+        CoreState { core }
+    }
+}
+
+/// A part of the architectural state that differs between two snapshots.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(
+        softcore_prelude::serde::Serialize,
+        softcore_prelude::serde::Deserialize
+    ),
+    serde(crate = "softcore_prelude::serde")
+)]
+pub struct Change {
+    /// The name of the register, CSR or field, using ABI names for registers.
+    pub name: String,
+    /// The value in the first snapshot.
+    pub before: String,
+    /// The value in the second snapshot.
+    pub after: String,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.name, self.before, self.after)
+    }
+}
+
+/// Returns the list of registers, CSRs and fields that differ between two snapshots.
+///
+/// Changes are reported in a fixed order: program counter and privilege mode first, then general
+/// purpose registers, CSRs, PMP entries, floating point and vector registers, and finally the
+/// internal state of the model.
+pub fn diff(a: &CoreState, b: &CoreState) -> Vec<Change> {
+    // Destructuring the core ensures all fields are compared, new registers fail to compile.
+    #[rustfmt::skip]
+    let Core {
+        PC, nextPC, x1, x2, x3, x4, x5, x6, x7, x8, x9, x10, x11, x12, x13, x14, x15, x16,
+        x17, x18, x19, x20, x21, x22, x23, x24, x25, x26, x27, x28, x29, x30, x31,
+        cur_privilege, cur_inst, misa, mstatus, menvcfg, senvcfg, mie, mip, medeleg,
+        mideleg, mtvec, mcause, mepc, mtval, mscratch, scounteren, mcounteren,
+        mcountinhibit, mcycle, mtime, minstret, minstret_increment, mvendorid, mimpid,
+        marchid, mhartid, mconfigptr, stvec, sscratch, sepc, scause, stval, tselect, vstart,
+        vl, vtype, pmpcfg_n, pmpaddr_n, vr0, vr1, vr2, vr3, vr4, vr5, vr6, vr7, vr8, vr9,
+        vr10, vr11, vr12, vr13, vr14, vr15, vr16, vr17, vr18, vr19, vr20, vr21, vr22, vr23,
+        vr24, vr25, vr26, vr27, vr28, vr29, vr30, vr31, vcsr, mhpmevent, mhpmcounter,
+        float_result, float_fflags, f0, f1, f2, f3, f4, f5, f6, f7, f8, f9, f10, f11, f12,
+        f13, f14, f15, f16, f17, f18, f19, f20, f21, f22, f23, f24, f25, f26, f27, f28, f29,
+        f30, f31, fcsr, mcyclecfg, minstretcfg, mtimecmp, stimecmp, htif_tohost, htif_done,
+        htif_exit_code, htif_cmd_write, htif_payload_writes, tlb, satp, hart_state, config,
+        mem: _,
+        platform: _,
+    } = &a.core;
+    let b = &b.core;
+    let mut changes = Vec::new();
+    let mut push = |name: String, before: String, after: String| {
+        changes.push(Change {
+            name,
+            before,
+            after,
+        })
+    };
+
+    macro_rules! compare {
+        // Bitvectors, displayed in hexadecimal
+        (bits $($field:ident => $name:literal),* $(,)?) => {
+            $(if *$field != b.$field {
+                push($name.to_string(), format!("{:#x}", $field), format!("{:#x}", b.$field));
+            })*
+        };
+        // Bitfields, displayed in hexadecimal
+        (bitfields $($field:ident => $name:literal),* $(,)?) => {
+            $(if *$field != b.$field {
+                push(
+                    $name.to_string(),
+                    format!("{:#x}", $field.bits),
+                    format!("{:#x}", b.$field.bits),
+                );
+            })*
+        };
+        // Any other type, displayed with its debug representation
+        (other $($field:ident => $name:literal),* $(,)?) => {
+            $(if *$field != b.$field {
+                push($name.to_string(), format!("{:?}", $field), format!("{:?}", b.$field));
+            })*
+        };
+    }
+
+    compare!(bits PC => "pc");
+    compare!(other cur_privilege => "privilege");
+    compare!(bits
+        x1 => "ra", x2 => "sp", x3 => "gp", x4 => "tp", x5 => "t0", x6 => "t1", x7 => "t2",
+        x8 => "s0", x9 => "s1", x10 => "a0", x11 => "a1", x12 => "a2", x13 => "a3", x14 => "a4",
+        x15 => "a5", x16 => "a6", x17 => "a7", x18 => "s2", x19 => "s3", x20 => "s4",
+        x21 => "s5", x22 => "s6", x23 => "s7", x24 => "s8", x25 => "s9", x26 => "s10",
+        x27 => "s11", x28 => "t3", x29 => "t4", x30 => "t5", x31 => "t6",
+    );
+    compare!(bitfields
+        misa => "misa", mstatus => "mstatus", menvcfg => "menvcfg", senvcfg => "senvcfg",
+        mie => "mie", mip => "mip", medeleg => "medeleg", mideleg => "mideleg",
+        mtvec => "mtvec", mcause => "mcause", stvec => "stvec", scause => "scause",
+        mcounteren => "mcounteren", scounteren => "scounteren",
+        mcountinhibit => "mcountinhibit", mcyclecfg => "mcyclecfg",
+        minstretcfg => "minstretcfg", fcsr => "fcsr", vtype => "vtype", vcsr => "vcsr",
+    );
+    compare!(bits
+        mepc => "mepc", mtval => "mtval", mscratch => "mscratch", sepc => "sepc",
+        stval => "stval", sscratch => "sscratch", satp => "satp", mcycle => "mcycle",
+        minstret => "minstret", mtime => "mtime", mtimecmp => "mtimecmp",
+        stimecmp => "stimecmp", mvendorid => "mvendorid", marchid => "marchid",
+        mimpid => "mimpid", mhartid => "mhartid", mconfigptr => "mconfigptr",
+        tselect => "tselect", vstart => "vstart", vl => "vl",
+    );
+    for (i, (before, after)) in mhpmcounter.iter().zip(&b.mhpmcounter).enumerate() {
+        if before != after {
+            push(
+                format!("mhpmcounter{i}"),
+                format!("{:#x}", before),
+                format!("{:#x}", after),
+            );
+        }
+    }
+    for (i, (before, after)) in mhpmevent.iter().zip(&b.mhpmevent).enumerate() {
+        if before != after {
+            push(
+                format!("mhpmevent{i}"),
+                format!("{:#x}", before.bits),
+                format!("{:#x}", after.bits),
+            );
+        }
+    }
+    for (i, (before, after)) in pmpcfg_n.iter().zip(&b.pmpcfg_n).enumerate() {
+        if before != after {
+            push(
+                format!("pmp{i}cfg"),
+                format!("{:#x}", before.bits),
+                format!("{:#x}", after.bits),
+            );
+        }
+    }
+    for (i, (before, after)) in pmpaddr_n.iter().zip(&b.pmpaddr_n).enumerate() {
+        if before != after {
+            push(
+                format!("pmpaddr{i}"),
+                format!("{:#x}", before),
+                format!("{:#x}", after),
+            );
+        }
+    }
+    compare!(bits
+        f0 => "ft0", f1 => "ft1", f2 => "ft2", f3 => "ft3", f4 => "ft4", f5 => "ft5",
+        f6 => "ft6", f7 => "ft7", f8 => "fs0", f9 => "fs1", f10 => "fa0", f11 => "fa1",
+        f12 => "fa2", f13 => "fa3", f14 => "fa4", f15 => "fa5", f16 => "fa6", f17 => "fa7",
+        f18 => "fs2", f19 => "fs3", f20 => "fs4", f21 => "fs5", f22 => "fs6", f23 => "fs7",
+        f24 => "fs8", f25 => "fs9", f26 => "fs10", f27 => "fs11", f28 => "ft8", f29 => "ft9",
+        f30 => "ft10", f31 => "ft11",
+    );
+    compare!(bits
+        vr0 => "v0", vr1 => "v1", vr2 => "v2", vr3 => "v3", vr4 => "v4", vr5 => "v5",
+        vr6 => "v6", vr7 => "v7", vr8 => "v8", vr9 => "v9", vr10 => "v10", vr11 => "v11",
+        vr12 => "v12", vr13 => "v13", vr14 => "v14", vr15 => "v15", vr16 => "v16",
+        vr17 => "v17", vr18 => "v18", vr19 => "v19", vr20 => "v20", vr21 => "v21",
+        vr22 => "v22", vr23 => "v23", vr24 => "v24", vr25 => "v25", vr26 => "v26",
+        vr27 => "v27", vr28 => "v28", vr29 => "v29", vr30 => "v30", vr31 => "v31",
+    );
+    compare!(bits
+        htif_tohost => "htif_tohost", htif_exit_code => "htif_exit_code",
+        htif_payload_writes => "htif_payload_writes",
+    );
+    compare!(other htif_done => "htif_done", htif_cmd_write => "htif_cmd_write");
+    for (i, (before, after)) in tlb.iter().zip(&b.tlb).enumerate() {
+        if before != after {
+            push(
+                format!("tlb[{i}]"),
+                format!("{:x?}", before),
+                format!("{:x?}", after),
+            );
+        }
+    }
+    compare!(bits
+        nextPC => "next_pc", cur_inst => "cur_inst", float_result => "float_result",
+        float_fflags => "float_fflags",
+    );
+    compare!(other
+        minstret_increment => "minstret_increment", hart_state => "hart_state",
+        config => "config",
+    );
+    changes
+}
@@ -0,0 +1,67 @@
+//! Instruction streams
+//!
+//! An [Instructions] iterator decodes a buffer of code one instruction at a time, keeping track of
+//! the length of each instruction so that callers can walk through mixed 16 and 32 bits
+//! encodings.
+
+use super::instruction_length;
+use super::raw::{Core, ast};
+use softcore_prelude::bv;
+
+/// An iterator over the instructions of a little-endian code buffer, see [Core::instructions].
+///
+/// Each item is a tuple `(offset, length, raw_bits, instruction)`, where `offset` and `length` are
+/// in bytes. Encodings that do not correspond to a valid instruction are returned as
+/// [ast::ILLEGAL] holding their lowest 32 bits: this includes illegal compressed encodings and
+/// 48 and 64 bits encodings, which are not supported by the specification. Encodings of 80 bits
+/// and more are skipped 16 bits at a time.
+///
+/// The iteration stops at a truncated instruction at the end of the buffer, the bytes left are
+/// available with [Instructions::remainder].
+pub struct Instructions<'a> {
+    core: &'a mut Core,
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Instructions<'a> {
+    pub(super) fn new(core: &'a mut Core, bytes: &'a [u8]) -> Self {
+        Instructions {
+            core,
+            bytes,
+            offset: 0,
+        }
+    }
+
+    /// Returns the bytes that have not been decoded yet.
+    pub fn remainder(&self) -> &'a [u8] {
+        &self.bytes[self.offset..]
+    }
+}
+
+impl Iterator for Instructions<'_> {
+    type Item = (usize, usize, u64, ast);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.offset;
+        let bytes = self.remainder();
+        let low = u16::from_le_bytes([*bytes.first()?, *bytes.get(1)?]);
+        let length = instruction_length(low);
+        let bytes = bytes.get(..length.unwrap_or(2))?;
+        let raw_bits = bytes
+            .iter()
+            .rev()
+            .fold(0, |bits, byte| (bits << 8) | *byte as u64);
+        self.offset += bytes.len();
+
+        let instr = match length {
+            Some(2) => match self.core.decode_compressed(low) {
+                ast::C_ILLEGAL(bits) => ast::ILLEGAL(bv(bits.bits())),
+                instr => instr,
+            },
+            Some(4) => self.core.decode_instr(raw_bits as u32),
+            _ => ast::ILLEGAL(bv(raw_bits & 0xffff_ffff)),
+        };
+        Some((offset, bytes.len(), raw_bits, instr))
+    }
+}
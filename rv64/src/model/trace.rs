@@ -0,0 +1,451 @@
+//! Retirement traces
+//!
+//! A retirement hook registered with [Core::set_retire_hook] is called after each retired
+//! instruction with a [Retired] record describing the instruction and its side effects. The
+//! specification notifies the [Platform](super::Platform) of the executed instruction, register
+//! and CSR writes, and branches, while memory accesses are recorded by the physical memory and
+//! related to their virtual address through the address translations of the specification.
+//!
+//! Retired instructions can be printed in the format of Spike's `--log-commits` output with
+//! [Retired::spike_commit], or logged directly with [Core::set_spike_commit_log].
+
+use super::raw::{self, Core, Privilege, ast};
+use super::registers::Csr;
+use super::run::has_trapped;
+use softcore_prelude::MemoryAccess;
+use std::{fmt, io};
+
+type RetireHook = Box<dyn FnMut(&Retired) + Send>;
+
+/// The trace state of a core, held by its [Platform](super::Platform).
+///
+/// The notifications of the specification are recorded while a retirement hook is set. The hook
+/// is not shared between clones: a cloned core starts without a hook.
+#[derive(Default)]
+pub(super) struct Trace {
+    hook: Option<Hook>,
+    notifications: Notifications,
+}
+
+/// What to do with retired instructions.
+enum Hook {
+    /// Call a function, see [Core::set_retire_hook].
+    Function(RetireHook),
+    /// Log them in Spike's format, see [Core::set_spike_commit_log].
+    SpikeLog(SpikeLog),
+}
+
+struct SpikeLog {
+    writer: Box<dyn io::Write + Send>,
+    hart_id: u64,
+    /// The first error reported by the writer since the last flush.
+    error: Option<io::Error>,
+}
+
+/// The notifications received from the specification since the start of the step.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Notifications {
+    instr: Option<ast>,
+    reg_writes: Vec<RegisterWrite>,
+    csr_writes: Vec<(CsrId, u64)>,
+    branch_target: Option<u64>,
+    mem_accesses: Vec<DataAccess>,
+    /// The virtual and physical addresses of the last successful translation.
+    translation: Option<(u64, u64)>,
+}
+
+/// An instruction retired by the core, along with its side effects.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Retired {
+    /// The address of the instruction.
+    pub pc: u64,
+    /// The raw encoding of the instruction, compressed instructions use the lower 16 bits.
+    pub bits: u32,
+    /// The decoded instruction, as executed by the core.
+    pub instr: ast,
+    /// The privilege mode the instruction executed in.
+    pub privilege: Privilege,
+    /// The registers written by the instruction, in program order.
+    pub reg_writes: Vec<RegisterWrite>,
+    /// The CSRs written by the instruction, including implicit writes such as `fflags`.
+    pub csr_writes: Vec<(CsrId, u64)>,
+    /// The target of the control transfer performed by the instruction, if any.
+    pub branch_target: Option<u64>,
+    /// The data memory accesses performed by the instruction, in program order.
+    pub mem_accesses: Vec<DataAccess>,
+    /// The value of the `vtype` CSR after the instruction, describing vector register writes.
+    pub vtype: u64,
+    /// The value of the `vl` CSR after the instruction.
+    pub vl: u64,
+}
+
+/// A data memory access performed by an instruction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DataAccess {
+    /// The virtual address of the access.
+    pub vaddr: u64,
+    /// The access, as performed on the physical memory.
+    pub access: MemoryAccess,
+}
+
+/// A write to a general purpose, floating point or vector register.
+///
+/// Writes to `x0` are not reported.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RegisterWrite {
+    /// A write to the general purpose register `x{index}`.
+    X(u8, u64),
+    /// A write to the floating point register `f{index}`.
+    F(u8, u64),
+    /// A write to the vector register `v{index}`, as little-endian bytes.
+    V(u8, Vec<u8>),
+}
+
+/// A CSR, as identified by the specification when notifying a write.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CsrId {
+    /// A CSR identified by its address.
+    Address(u64),
+    /// A CSR identified by its name.
+    Name(&'static str),
+}
+
+/// Displays a retired instruction in the format of Spike's `--log-commits` output, see
+/// [Retired::spike_commit].
+pub struct SpikeCommit<'a> {
+    retired: &'a Retired,
+    hart_id: u64,
+}
+
+/// The state of the core before a traced step.
+pub(super) struct TraceStart {
+    pc: u64,
+    privilege: Privilege,
+}
+
+impl Core {
+    /// Register a hook called after each retired instruction.
+    ///
+    /// Replaces the current hook, if any. Tracing slows down execution, as the side effects of
+    /// each instruction are recorded.
+    pub fn set_retire_hook(&mut self, hook: impl FnMut(&Retired) + Send + 'static) {
+        self.set_hook(Hook::Function(Box::new(hook)));
+    }
+
+    /// Log retired instructions to `writer`, in the format of Spike's `--log-commits` output, as
+    /// executed by the hart `hart_id`.
+    ///
+    /// This replaces the retirement hook, if any. Errors reported by the writer are returned by
+    /// [Core::flush_spike_commit_log], which should be called once done logging.
+    pub fn set_spike_commit_log(&mut self, hart_id: u64, writer: impl io::Write + Send + 'static) {
+        self.set_hook(Hook::SpikeLog(SpikeLog {
+            writer: Box::new(writer),
+            hart_id,
+            error: None,
+        }));
+    }
+
+    /// Flush the Spike commit log, if any.
+    ///
+    /// Returns the first error reported by the writer since the last flush, if any.
+    pub fn flush_spike_commit_log(&mut self) -> io::Result<()> {
+        let Some(Hook::SpikeLog(log)) = &mut self.platform.trace.hook else {
+            return Ok(());
+        };
+        match log.error.take() {
+            Some(err) => Err(err),
+            None => log.writer.flush(),
+        }
+    }
+
+    /// Remove the retirement hook or Spike commit log.
+    ///
+    /// The Spike commit log is dropped without reporting errors, see
+    /// [Core::flush_spike_commit_log].
+    pub fn clear_retire_hook(&mut self) {
+        self.platform.trace = Trace::new();
+        self.mem.record_accesses(false);
+    }
+
+    fn set_hook(&mut self, hook: Hook) {
+        self.platform.trace = Trace {
+            hook: Some(hook),
+            notifications: Notifications::new(),
+        };
+        self.mem.record_accesses(true);
+    }
+
+    /// Capture the state required to trace a step, if a hook is registered.
+    pub(super) fn trace_start(&mut self) -> Option<TraceStart> {
+        if !self.platform.trace.is_enabled() {
+            return None;
+        }
+        self.platform.trace.notifications = Notifications::new();
+        self.mem.take_accesses();
+        Some(TraceStart {
+            pc: self.PC.bits(),
+            privilege: self.cur_privilege,
+        })
+    }
+
+    /// Call the retirement hook if the step retired an instruction.
+    pub(super) fn trace_end(&mut self, start: TraceStart, step: &raw::Step) {
+        let accesses = self.mem.take_accesses();
+        self.platform.trace.mem_accesses(accesses);
+        let notifications = std::mem::take(&mut self.platform.trace.notifications);
+        let mut mem_accesses = notifications.mem_accesses;
+        let raw::Step::Step_Execute((_, bits)) = step else {
+            return;
+        };
+        let Some(instr) = notifications.instr else {
+            return;
+        };
+        if has_trapped(step) {
+            return;
+        }
+
+        // The instruction is fetched 16 bits at a time before it executes.
+        let bits = bits.bits() as u32;
+        let fetch_parcels = if bits & 0b11 != 0b11 { 1 } else { 2 };
+        let fetches = mem_accesses
+            .iter()
+            .take(fetch_parcels)
+            .take_while(|data| !data.access.is_write && data.access.width == 2)
+            .count();
+        mem_accesses.drain(..fetches);
+
+        let retired = Retired {
+            pc: start.pc,
+            bits,
+            instr,
+            privilege: start.privilege,
+            reg_writes: notifications.reg_writes,
+            csr_writes: notifications.csr_writes,
+            branch_target: notifications.branch_target,
+            mem_accesses,
+            vtype: self.vtype(),
+            vl: self.vl(),
+        };
+        match &mut self.platform.trace.hook {
+            Some(Hook::Function(hook)) => hook(&retired),
+            Some(Hook::SpikeLog(log)) => {
+                let result = writeln!(log.writer, "{}", retired.spike_commit(log.hart_id));
+                if let Err(err) = result {
+                    log.error.get_or_insert(err);
+                }
+            }
+            None => (),
+        }
+    }
+}
+
+impl Trace {
+    pub(super) const fn new() -> Self {
+        Trace {
+            hook: None,
+            notifications: Notifications::new(),
+        }
+    }
+
+    /// Returns true if a retirement hook is set.
+    pub(super) fn is_enabled(&self) -> bool {
+        self.hook.is_some()
+    }
+
+    /// Record the executed instruction.
+    ///
+    /// Some instructions execute another instruction (e.g. compressed instructions executing
+    /// their uncompressed counterpart), only the first one is recorded.
+    pub(super) fn instr_announce(&mut self, instr: ast) {
+        if self.is_enabled() && self.notifications.instr.is_none() {
+            self.notifications.instr = Some(instr);
+        }
+    }
+
+    pub(super) fn reg_write(&mut self, write: RegisterWrite) {
+        if self.is_enabled() {
+            self.notifications.reg_writes.push(write);
+        }
+    }
+
+    pub(super) fn csr_write(&mut self, csr: CsrId, value: u64) {
+        if self.is_enabled() {
+            self.notifications.csr_writes.push((csr, value));
+        }
+    }
+
+    pub(super) fn branch_announce(&mut self, target: u64) {
+        if self.is_enabled() {
+            self.notifications.branch_target = Some(target);
+        }
+    }
+
+    /// Record the translation of `vaddr`, into `paddr` unless the translation failed.
+    pub(super) fn translation(&mut self, vaddr: u64, paddr: Option<u64>) {
+        if self.is_enabled() {
+            self.notifications.translation = paddr.map(|paddr| (vaddr, paddr));
+        }
+    }
+
+    /// Record memory accesses, which are performed on the last translated address.
+    pub(super) fn mem_accesses(&mut self, accesses: Vec<MemoryAccess>) {
+        if !self.is_enabled() {
+            return;
+        }
+        let translation = self.notifications.translation;
+        let accesses = accesses.into_iter().map(|access| {
+            let vaddr = match translation {
+                Some((vaddr, paddr)) => vaddr.wrapping_add(access.addr.wrapping_sub(paddr)),
+                None => access.addr,
+            };
+            DataAccess { vaddr, access }
+        });
+        self.notifications.mem_accesses.extend(accesses);
+    }
+}
+
+impl Notifications {
+    const fn new() -> Self {
+        Notifications {
+            instr: None,
+            reg_writes: Vec::new(),
+            csr_writes: Vec::new(),
+            branch_target: None,
+            mem_accesses: Vec::new(),
+            translation: None,
+        }
+    }
+}
+
+/// Clones start without a retirement hook, as hooks can not be shared.
+impl Clone for Trace {
+    fn clone(&self) -> Self {
+        Trace::new()
+    }
+}
+
+impl fmt::Debug for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Trace")
+            .field("enabled", &self.is_enabled())
+            .field("notifications", &self.notifications)
+            .finish()
+    }
+}
+
+/// Hooks can not be compared: traces are equal if both or neither have a hook, and they recorded
+/// the same notifications.
+impl PartialEq for Trace {
+    fn eq(&self, other: &Self) -> bool {
+        self.is_enabled() == other.is_enabled() && self.notifications == other.notifications
+    }
+}
+
+impl Eq for Trace {}
+
+impl CsrId {
+    /// Returns the CSR, if known to the model.
+    pub fn csr(&self) -> Option<Csr> {
+        match self {
+            CsrId::Address(address) => Csr::from_address(*address),
+            CsrId::Name(name) => Csr::from_name(name),
+        }
+    }
+}
+
+impl Retired {
+    /// Returns a value displaying the instruction in the format of Spike's `--log-commits` output,
+    /// for instance:
+    ///
+    /// ```text
+    /// core   0: 3 0x0000000080000000 (0x00000297) x5  0x0000000080000000
+    /// ```
+    ///
+    /// Vector register writes are preceded by the element width, register group multiplier and
+    /// vector length, as in `e64 m1 l2 v8  0x...`. Register and CSR writes are followed by the
+    /// data memory accesses: loads are annotated with their virtual address, and stores with
+    /// their virtual address and value.
+    ///
+    /// CSRs unknown to the model are printed as `c{address}_unknown`. As Spike identifies CSRs by
+    /// address, writes notified with a name unknown to the model are left out.
+    pub fn spike_commit(&self, hart_id: u64) -> SpikeCommit<'_> {
+        SpikeCommit {
+            retired: self,
+            hart_id,
+        }
+    }
+}
+
+impl fmt::Display for SpikeCommit<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let retired = self.retired;
+        let privilege = match retired.privilege {
+            Privilege::User => 0,
+            Privilege::Supervisor => 1,
+            Privilege::Machine => 3,
+        };
+        write!(
+            f,
+            "core{:4}: {} {:#018x}",
+            self.hart_id, privilege, retired.pc
+        )?;
+        if retired.bits & 0b11 != 0b11 {
+            write!(f, " ({:#06x})", retired.bits)?;
+        } else {
+            write!(f, " ({:#010x})", retired.bits)?;
+        }
+
+        let mut prev_vector = false;
+        for write in &retired.reg_writes {
+            match write {
+                RegisterWrite::X(reg, value) => write!(f, " x{reg:<2} {value:#018x}")?,
+                RegisterWrite::F(reg, value) => write!(f, " f{reg:<2} {value:#018x}")?,
+                RegisterWrite::V(reg, bytes) => {
+                    // Consecutive vector writes share the vector configuration
+                    if !prev_vector {
+                        write_vector_config(f, retired.vtype, retired.vl)?;
+                    }
+                    write!(f, " v{reg:<2} 0x")?;
+                    for byte in bytes.iter().rev() {
+                        write!(f, "{byte:02x}")?;
+                    }
+                }
+            }
+            prev_vector = matches!(write, RegisterWrite::V(..));
+        }
+        for (id, value) in &retired.csr_writes {
+            match (id.csr(), id) {
+                (Some(csr), _) => write!(f, " c{}_{}", csr.address(), csr)?,
+                (None, CsrId::Address(address)) => write!(f, " c{address}_unknown")?,
+                (None, CsrId::Name(_)) => continue,
+            }
+            write!(f, " {value:#018x}")?;
+        }
+
+        let (stores, loads): (Vec<&DataAccess>, Vec<&DataAccess>) = retired
+            .mem_accesses
+            .iter()
+            .partition(|data| data.access.is_write);
+        for load in loads {
+            write!(f, " mem {:#018x}", load.vaddr)?;
+        }
+        for store in stores {
+            let (vaddr, value) = (store.vaddr, store.access.value);
+            let digits = 2 + 2 * store.access.width;
+            write!(f, " mem {vaddr:#018x} {value:#0digits$x}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes the vector configuration in Spike's format, such as `e32 mf2 l3`.
+fn write_vector_config(f: &mut fmt::Formatter<'_>, vtype: u64, vl: u64) -> fmt::Result {
+    let sew = 8 << ((vtype >> 3) & 0b111);
+    // The multiplier is a signed power of two
+    let lmul = (((vtype & 0b111) as i8) << 5) >> 5;
+    if lmul < 0 {
+        write!(f, " e{sew} mf{} l{vl}", 1 << -lmul)
+    } else {
+        write!(f, " e{sew} m{} l{vl}", 1 << lmul)
+    }
+}
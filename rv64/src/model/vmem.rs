@@ -0,0 +1,50 @@
+//! Virtual memory
+//!
+//! Address translation is performed by the `translateAddr` function of the specification. The
+//! page table walker of the specification is generic over the width of virtual addresses, the
+//! Rust back-end specializes it for each translation mode of RV64 (Sv39, Sv48 and Sv57).
+
+use super::raw::{self, AccessType, Core, ExceptionType, Privilege};
+use softcore_prelude::bv;
+
+impl Core {
+    /// Translate a virtual address into a physical address.
+    ///
+    /// The translation follows the rules of the specification: the effective privilege mode
+    /// (taking `mstatus.MPRV` into account for data accesses) and `satp` determine the
+    /// translation mode, then the TLB is looked up and, on a miss, the page tables are walked in
+    /// the memory backend and the TLB is filled. Accessed and dirty bits are updated in memory if
+    /// hardware updates are enabled (`menvcfg.ADUE`), otherwise a page fault is raised when they
+    /// would need to be set.
+    ///
+    /// Translation modes that are not enabled in the configuration can not be written to `satp`
+    /// with CSR instructions. If such a mode is assigned to the `satp` register directly, the
+    /// translation raises a page fault.
+    pub fn translate(
+        &mut self,
+        vaddr: u64,
+        access_kind: AccessType<()>,
+    ) -> Result<u64, ExceptionType> {
+        let privilege =
+            raw::effectivePrivilege(self, access_kind, self.mstatus, self.cur_privilege);
+        if privilege != Privilege::Machine && !self.is_satp_legal() {
+            let error = raw::PTW_Error::PTW_Invalid_Addr(());
+            return Err(raw::translationException(self, access_kind, error));
+        }
+
+        let vaddr = raw::virtaddr::Virtaddr(bv(vaddr));
+        match raw::translateAddr(self, vaddr, access_kind) {
+            raw::TR_Result::TR_Address((raw::physaddr::Physaddr(paddr), _)) => Ok(paddr.bits()),
+            raw::TR_Result::TR_Failure((exception, _)) => Err(exception),
+        }
+    }
+
+    /// Returns true if `satp` holds a translation mode enabled in the configuration.
+    ///
+    /// The specification legalizes `satp` on CSR writes, an illegal value is replaced by the
+    /// previous one.
+    fn is_satp_legal(&mut self) -> bool {
+        let satp = self.satp;
+        raw::legalize_satp(self, raw::Architecture::RV64, bv(0), satp) == satp
+    }
+}
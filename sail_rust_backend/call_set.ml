@@ -185,8 +185,8 @@ let rec get_call_set_rec (ast : (tannot, env) ast) (ctx : sail_ctx) : sail_ctx =
 let rec get_call_set (ast : (tannot, env) ast) : sail_ctx =
   let call_set =
     SSet.of_list
-      [ (* "execute"; *)
-        "CSR"
+      [ "execute"
+      ; "CSR"
       ; "MRET"
       ; "SRET"
       ; "ITYPE"
@@ -198,11 +198,22 @@ let rec get_call_set (ast : (tannot, env) ast) : sail_ctx =
       ; "HFENCE_GVMA"
       ; (* Decoder *)
         "encdec_backwards"
+      ; "encdec_compressed_backwards"
+      ; (* Encoder *)
+        "encdec_forwards"
+      ; "encdec_forwards_matches"
+      ; "encdec_compressed_forwards"
+      ; "encdec_compressed_forwards_matches"
       ; (* Registers *)
         "rX"
       ; "wX"
       ; "is_CSR_defined"
       ; "creg2reg_idx"
+      ; (* Virtual memory *)
+        "translateAddr"
+      ; "effectivePrivilege"
+      ; "legalize_satp"
+      ; "translationException"
       ; (* PMP checks *)
         "pmpCheck"
       ; "pmpWriteAddrReg"
@@ -217,8 +228,13 @@ let rec get_call_set (ast : (tannot, env) ast) : sail_ctx =
         "read_CSR"
       ; "write_CSR"
       ; "doCSR"
+      ; "csr_name_map_forwards"
+      ; (* Disassembler *)
+        "assembly_forwards"
       ; (* System reset *)
         "reset_sys"
+      ; (* Fetch-decode-execute loop *)
+        "try_step"
       ]
   in
   let sail_ctx = { call_set; config_map = SMap.empty } in
@@ -21,6 +21,7 @@ type context =
   ; config_map : Call_set.config_map
   ; registers : SSet.t
   ; enum_entries : (string * string) list
+  ; vlen_exp : int option (* The length of vector registers, if set on the command line *)
   ; mutable uses_sail_ctx : bool
   }
 
@@ -15,9 +15,21 @@ module SMap = Call_set.SMap
 
 type core_config = int SMap.t
 
-(** The RV64 static configuration **)
-let rv64_config : core_config =
-  SMap.of_list [ "extensions.V.vlen_exp", 3 (* Must be in range(3, 16) *) ]
+(** The default length of vector registers, as a power of two.
+
+    Must be in range(3, 16), but bitvectors are currently limited to 512 bits,
+    so values above 9 are not supported. **)
+let default_vlen_exp = 3
+
+(** The widths of virtual addresses supported on RV64 (Sv39, Sv48 and Sv57).
+
+    The page table walker is specialized for each of these widths. **)
+let sv_widths = [ 39; 48; 57 ]
+
+(** The RV64 static configuration, for the given length of vector registers **)
+let rv64_config (vlen_exp : int option) : core_config =
+  let vlen_exp = Option.value vlen_exp ~default:default_vlen_exp in
+  SMap.of_list [ "extensions.V.vlen_exp", vlen_exp ]
 ;;
 
 (** Returns the config value, if known at compile time. **)
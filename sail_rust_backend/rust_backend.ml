@@ -297,6 +297,9 @@ module Codegen () = struct
         (* set flag *)
         RsField (RsId core_ctx, id))
       else RsId id
+    | E_lit (L_aux (L_string s, _)) ->
+      (* Strings are owned, as mappings such as `assembly` build them at runtime *)
+      RsApp (RsId "String::from", [], [ RsLit (RsLitStr s) ])
     | E_lit lit -> RsLit (process_lit lit)
     | E_typ (typ, exp) -> RsAs (process_exp ctx exp, typ_to_rust typ)
     | E_app (id, [ e1; e2 ]) when string_of_id id = "mult_atom" ->
@@ -378,13 +381,18 @@ module Codegen () = struct
             (* Then we add them to the current environment *)
             let env = add_existential (fst aux) kind_ids constraints env in
             (* And finally we use the SMT solver to prove that our approximation is conservative *)
-            if prove __POS__ env (nc_lteq n (nconstant (Big_int.of_int 64)))
+            let fits_in width = prove __POS__ env (nc_lteq n (nconstant (Big_int.of_int width))) in
+            if fits_in 64
             then RsApp (RsId id, [ "64" ], exp_list)
+            else if fits_in max_bitvector_width
+            then RsApp (RsId id, [ string_of_int max_bitvector_width ], exp_list)
             else
               Reporting.unreachable
                 (fst aux)
                 __POS__
-                "Could not prove that the bit width is less or equal to 64")
+                (Printf.sprintf
+                   "Could not prove that the bit width is less or equal to %d"
+                   max_bitvector_width))
        (* We found the type variable, but it is not a nexp! *)
        | Some (A_aux (_, l)) -> Reporting.unreachable l __POS__ "Expected a nexp")
     | E_app (id, [ size; item ]) when string_of_id id = "vector_init" ->
@@ -470,7 +478,7 @@ module Codegen () = struct
     | E_internal_assume (n_constraint, exp) -> RsTodo "E_internal_assume"
     | E_constraint n_constraint -> RsTodo "E_constraint"
     | E_config cfgs ->
-      (match config_find rv64_config cfgs with
+      (match config_find (rv64_config ctx.vlen_exp) cfgs with
        (* known values are inlined directly *)
        | Some value -> mk_num value
        (* All other values are retrieved from the core context *)
@@ -827,10 +835,11 @@ module Codegen () = struct
     : rs_enum
     =
     let id = string_of_id id in
+    let fields = process_unions members in
     { name = id
     ; generics = typequant_to_generics typq
-    ; fields = process_unions members
-    ; derive = default_copy_derive
+    ; fields
+    ; derive = derive_for_fields (List.filter_map snd fields)
     ; doc = [ id; ""; loc_to_doc l ]
     }
 
@@ -839,11 +848,12 @@ module Codegen () = struct
     =
     let to_rs_fields ((typ, id) : typ * id) = string_of_id id, typ_to_rust typ in
     let id = string_of_id id in
+    let fields = List.map to_rs_fields fields in
     RsStruct
       { name = id
       ; generics = typequant_to_generics typeq
-      ; fields = List.map to_rs_fields fields
-      ; derive = default_copy_derive
+      ; fields
+      ; derive = derive_for_fields (List.map snd fields)
       ; doc = [ id; ""; loc_to_doc l ]
       }
 
@@ -978,12 +988,22 @@ module Codegen () = struct
     let config_field =
       if List.length config_structs = 0 then [] else [ "config", RsTypId "Config" ]
     in
+    let memory_field =
+      if Rust_transform.uses_memory ctx.call_set
+      then [ "mem", RsTypId "PhysicalMemory" ]
+      else []
+    in
+    let platform_field =
+      if Rust_transform.uses_platform ctx.call_set
+      then [ "platform", RsTypId "super::Platform" ]
+      else []
+    in
     let registers = gather_registers defs |> List.map (fun (name, typ, _) -> name, typ) in
     RsProg
       ([ RsStruct
            { name = "Core"
            ; generics = []
-           ; fields = registers @ config_field
+           ; fields = registers @ config_field @ memory_field @ platform_field
            ; derive = [ "Eq"; "PartialEq"; "Clone"; "Debug" ]
            ; doc =
                [ "The software core."
@@ -997,6 +1017,24 @@ module Codegen () = struct
                   available in the `raw` module, whereas higher-level wrappers are \
                   implemented as methods on the [Core] struct directly."
                ]
+               @ (if memory_field = []
+                  then []
+                  else
+                    [ ""
+                    ; "Comparing cores also compares their physical memory, see the \
+                       [PartialEq] implementation of [PhysicalMemory]."
+                    ])
+               @ (if platform_field = []
+                  then []
+                  else
+                    [ ""
+                    ; "The platform is provided by the crate embedding the model, as the \
+                       `Platform` type of the parent module. It is notified of the side \
+                       effects of the specification, and physical memory writes and \
+                       address translations are performed through the `write_mem` and \
+                       `translate_addr` methods of the core, which the embedding crate must \
+                       implement."
+                    ])
            }
        ]
        @ config_structs)
@@ -1060,6 +1098,14 @@ module Codegen () = struct
     match n with
     | NC_true -> RsLit RsLitTrue
     | NC_false -> RsLit RsLitFalse
+    | NC_equal (A_aux (A_nexp n1, _), A_aux (A_nexp n2, _)) ->
+      RsBinop (nexp_to_rs_exp n1, RsBinopEq, nexp_to_rs_exp n2)
+    | NC_not_equal (A_aux (A_nexp n1, _), A_aux (A_nexp n2, _)) ->
+      RsBinop (nexp_to_rs_exp n1, RsBinopNeq, nexp_to_rs_exp n2)
+    | NC_and (nc1, nc2) ->
+      RsBinop (nconstraint_to_rs_exp nc1, RsBinopLAnd, nconstraint_to_rs_exp nc2)
+    | NC_or (nc1, nc2) ->
+      RsBinop (nconstraint_to_rs_exp nc1, RsBinopLOr, nconstraint_to_rs_exp nc2)
     | _ -> RsTodo "TodoNConstraint"
 
   and nexp_to_rs_exp (nexp : nexp) : rs_exp =
@@ -1082,7 +1128,11 @@ module Codegen () = struct
     | Nexp_id id -> RsId (string_of_id id)
     | Nexp_var kid -> RsId (sanitize_generic_id (string_of_kid kid)) (* variable *)
     | Nexp_app (fn, args) -> RsTodo "TodoAppExpr" (* app *)
-    | Nexp_if (cond, if_block, else_block) -> RsTodo "TodoIfExpr" (* if-then-else *)
+    | Nexp_if (cond, if_block, else_block) ->
+      RsIf
+        ( nconstraint_to_rs_exp cond
+        , nexp_to_rs_exp if_block
+        , nexp_to_rs_exp else_block )
 
   and get_first_two_elements lst =
     assert (List.length lst = 2);
@@ -1125,8 +1175,12 @@ module Codegen () = struct
     | Nexp_id id -> RsTypParamNum (RsId (sanitize_generic_id (string_of_id id)))
     | Nexp_var var ->
       RsTypParamTyp (RsTypId (capitalize_after_removal (string_of_kid var)))
-    | Nexp_times (_, _) | Nexp_sum (_, _) | Nexp_minus (_, _) | Nexp_exp _ | Nexp_neg _ ->
-      RsTypParamNum (RsBlock [ nexp_to_rs_exp (Nexp_aux (nexp, l)) ])
+    | Nexp_times (_, _)
+    | Nexp_sum (_, _)
+    | Nexp_minus (_, _)
+    | Nexp_exp _
+    | Nexp_neg _
+    | Nexp_if (_, _, _) -> RsTypParamNum (RsBlock [ nexp_to_rs_exp (Nexp_aux (nexp, l)) ])
     | _ -> RsTypParamTyp (RsTypId "TodoNexpOther")
   ;;
 
@@ -1284,7 +1338,7 @@ module Codegen () = struct
     funs obj
   ;;
 
-  let compile_ast env effect_info ast =
+  let compile_ast vlen_exp env effect_info ast =
     try
       (* Compute call set *)
       let sail_ctx = get_call_set ast in
@@ -1302,6 +1356,7 @@ module Codegen () = struct
         ; config_map = sail_ctx.config_map
         ; registers = Util.StringSet.of_list (gather_registers_list ast)
         ; enum_entries = process_enum_entries ast.defs
+        ; vlen_exp
         ; uses_sail_ctx = false
         }
       in
@@ -181,11 +181,33 @@ type rs_program = RsProg of rs_obj list
 let core_ctx = "core_ctx"
 let default_copy_derive = [ "Eq"; "PartialEq"; "Clone"; "Copy"; "Debug" ]
 let default_move_derive = [ "Eq"; "PartialEq"; "Clone"; "Debug" ]
+
+(* Strings are owned, types holding a string can not be Copy *)
+let rec contains_string (typ : rs_type) : bool =
+  match typ with
+  | RsTypId "string" -> true
+  | RsTypTuple types -> List.exists contains_string types
+  | RsTypGenericParam (_, params) -> List.exists param_contains_string params
+  | RsTypArray (typ, _) | RsTypOption typ -> param_contains_string typ
+  | _ -> false
+
+and param_contains_string (param : rs_type_param) : bool =
+  match param with
+  | RsTypParamTyp typ -> contains_string typ
+  | RsTypParamNum _ -> false
+;;
+
+let derive_for_fields (fields : rs_type list) : string list =
+  if List.exists contains_string fields then default_move_derive else default_copy_derive
+;;
 let nat_typ = RsTypId "u128"
 let int_typ = RsTypId "i128"
 let bool_typ = RsTypId "bool"
 let usize_typ = RsTypId "usize"
 
+(* Must match `MAX_BITVECTOR_WIDTH` in the Rust prelude *)
+let max_bitvector_width = 512
+
 let merge_rs_prog (prog1 : rs_program) (prog2 : rs_program) : rs_program =
   let (RsProg fn1) = prog1 in
   let (RsProg fn2) = prog2 in
@@ -296,9 +318,21 @@ let rec string_of_doc (doc : string list) : string =
 
 let string_of_const (const : bool) : string = if const then "const " else ""
 
+(* Serde support is enabled by the `serde` feature of the generated crate, through the serde
+   re-export of the prelude. *)
+let serde_derive =
+  "#[cfg_attr(feature = \"serde\", derive(serde::Serialize, serde::Deserialize), \
+   serde(crate = \"softcore_prelude::serde\"))]\n"
+;;
+
+(* Serde only supports arrays of up to 32 elements out of the box *)
+let serde_array_attribute =
+  "#[cfg_attr(feature = \"serde\", serde(with = \"softcore_prelude::serde_array\"))]\n"
+;;
+
 let string_of_derive (derive : string list) : string =
   match derive with
-  | head :: tail -> "#[derive(" ^ String.concat ", " derive ^ ")]\n"
+  | head :: tail -> "#[derive(" ^ String.concat ", " derive ^ ")]\n" ^ serde_derive
   | [] -> ""
 ;;
 
@@ -326,6 +360,22 @@ let string_of_generics_parameters (generics : rs_generic list) : string =
   | _ -> Printf.sprintf "<%s>" (String.concat ", " generics)
 ;;
 
+(* Bitvectors are only defined for the widths supported by the prelude, which must be stated for
+   each generic width. *)
+let string_of_where_clause (generics : rs_generic list) : string =
+  let bounds =
+    List.filter_map
+      (fun generic ->
+        match generic with
+        | RsGenConst (s, "i128") -> Some (Printf.sprintf "Width<%s>: SupportedWidth" s)
+        | _ -> None)
+      generics
+  in
+  match bounds with
+  | [] -> ""
+  | _ -> Printf.sprintf " where %s" (String.concat ", " bounds)
+;;
+
 let rec string_of_rs_type (typ : rs_type) : string =
   match typ with
   | RsTypId s -> s
@@ -596,15 +646,17 @@ let string_of_rs_fn (fn : rs_fn) : string =
     | ret_type -> Printf.sprintf " -> %s" (string_of_rs_type ret_type)
   in
   let generics = string_of_generics_parameters fn.signature.generics in
+  let where_clause = string_of_where_clause fn.signature.generics in
   let signature =
     Printf.sprintf
-      "%spub %sfn %s%s(%s)%s {\n%s"
+      "%spub %sfn %s%s(%s)%s%s {\n%s"
       doc
       const
       fn.name
       generics
       args
       ret_type
+      where_clause
       (indent 1)
   in
   let stmts =
@@ -637,18 +689,25 @@ let parse_enum_fields (entries : (string * rs_type option) list) : string =
 let string_of_rs_enum (enum : rs_enum) : string =
   let doc = string_of_doc enum.doc in
   Printf.sprintf
-    "%s%spub enum %s%s {\n%s\n}"
+    "%s%spub enum %s%s%s {\n%s\n}"
     doc
     (string_of_derive enum.derive)
     enum.name
     (string_of_generics_parameters enum.generics)
+    (string_of_where_clause enum.generics)
     (parse_enum_fields enum.fields)
 ;;
 
 let parse_struct_fields (entries : (string * rs_type) list) : string =
+  let attribute typ =
+    match typ with
+    | RsTypArray _ -> "    " ^ serde_array_attribute
+    | _ -> ""
+  in
   let prefixed_entries =
     List.map
-      (fun s -> "    pub " ^ fst s ^ ": " ^ string_of_rs_type (snd s) ^ ",\n")
+      (fun s ->
+        attribute (snd s) ^ "    pub " ^ fst s ^ ": " ^ string_of_rs_type (snd s) ^ ",\n")
       entries
   in
   let merged_fields = String.concat "" prefixed_entries in
@@ -659,11 +718,12 @@ let string_of_rs_struct (struc : rs_struct) : string =
   let generics = string_of_generics_parameters struc.generics in
   let attributes = string_of_derive struc.derive in
   Printf.sprintf
-    "%s%spub struct %s%s {\n%s\n}"
+    "%s%spub struct %s%s%s {\n%s\n}"
     (string_of_doc struc.doc)
     attributes
     struc.name
     generics
+    (string_of_where_clause struc.generics)
     (parse_struct_fields struc.fields)
 ;;
 
@@ -31,7 +31,6 @@ let external_func : SSet.t =
     ; "__exit"
     ; "signed"
     ; "lteq_int"
-    ; "sail_branch_announce"
     ; "bitvector_length"
     ; "bits_str"
     ; "print_reg"
@@ -45,11 +44,13 @@ let external_func : SSet.t =
     ; "subrange_bits"
     ; "internal_error"
     ; "bitvector_update"
+    ; "hex_bits_forwards"
     ; "hex_bits_12_forwards"
     ; "hex_bits_12_backwards"
     ; "sail_zeros"
     ; "parse_hex_bits"
     ; "get_slice_int"
+    ; "String::from"
     ]
 ;;
 
@@ -385,6 +386,9 @@ let bitvec_transform_exp (ctx : context) (exp : rs_exp) : rs_exp =
   (* If the size is not a constant, then rely on Rust type inference *)
   | RsApp (RsId "sail_zero_extend", generics, [ e; size ]) ->
     RsMethodApp { exp = e; name = "zero_extend"; generics = []; args = [] }
+  | RsApp (RsId "sign_extend", generics, [ RsLit (RsLitNum size); e ]) ->
+    RsMethodApp
+      { exp = e; name = "sign_extend"; generics = [ Big_int.to_string size ]; args = [] }
   | RsMatch (exp, pat :: pats) when is_bitvec_lit pat ->
     let method_app = { exp; name = "bits"; generics = []; args = [] } in
     RsMatch (RsMethodApp method_app, pat :: pats)
@@ -668,14 +672,80 @@ let nested_block_remover =
 let unsupported_fun : SSet.t =
   SSet.of_list
     [ (* Used only for side effects, not necessary in the Rust back-end *)
-      "csr_name_write_callback"
-    ; "csr_id_write_callback"
-    ; "csr_full_write_callback"
+      "csr_full_write_callback"
     ; "long_csr_write_callback"
-    ; "hex_bits_forwards"
     ]
 ;;
 
+(* CSR write and branch notifications are forwarded to the platform attached to the core. *)
+let trace_fun : SSet.t =
+  SSet.of_list [ "csr_name_write_callback"; "csr_id_write_callback"; "sail_branch_announce" ]
+;;
+
+let platform_method_app (name : string) (args : rs_exp list) : rs_exp =
+  mk_method_app (RsField (RsId core_ctx, "platform")) name args
+;;
+
+(* Memory accesses are forwarded to the memory backend attached to the core. *)
+let memory_fun : SSet.t =
+  SSet.of_list
+    [ "__read_mem"
+    ; "read_mem"
+    ; "__write_mem"
+    ; "write_mem"
+    ; "within_phys_mem"
+    ; "ext_check_phys_mem_read"
+    ; "ext_check_phys_mem_write"
+    ]
+;;
+
+(* Whether the model accesses memory, in which case the core needs a memory backend. *)
+let uses_memory (call_set : SSet.t) : bool =
+  SSet.exists (fun fn -> SSet.mem fn memory_fun) call_set
+;;
+
+(* Whether the core needs a platform, that is the [Platform] type provided by the crate embedding
+   the model. The platform is notified of side effects such as CSR writes, and sees physical
+   memory writes so that memory-mapped devices can be modelled. *)
+let uses_platform (call_set : SSet.t) : bool =
+  uses_memory call_set || SSet.exists (fun fn -> SSet.mem fn trace_fun) call_set
+;;
+
+let memory_method_app (name : string) (args : rs_exp list) : rs_exp =
+  mk_method_app (RsField (RsId core_ctx, "mem")) name args
+;;
+
+(* Physical memory writes go through the core rather than the memory backend, so that the
+   platform can intercept writes to memory-mapped devices. Address translations go through the
+   core as well, so that the platform can relate physical accesses to virtual addresses. *)
+let core_method_app (name : string) (args : rs_exp list) : rs_exp =
+  mk_method_app (RsId core_ctx) name args
+;;
+
+(* The platform checks preceding each physical access only look up whether the address is mapped
+   by the memory backend, the access itself is performed by the subsequent read or write. *)
+let memory_check (paddr : rs_exp) (width : rs_exp) (fault : rs_exp) : rs_exp =
+  let unwrap_paddr = RsPatApp (RsPatId "Physaddr", [ RsPatId "addr" ]) in
+  let check =
+    RsIf
+      ( memory_method_app "is_mapped" [ RsId "addr"; width ]
+      , RsApp (RsId "Ext_PhysAddr_OK", [], [ RsLit RsLitUnit ])
+      , RsApp (RsId "Ext_PhysAddr_Error", [], [ fault ]) )
+  in
+  RsMatch (paddr, [ RsPexp (unwrap_paddr, check) ])
+;;
+
+let access_fault (access_type : rs_exp) : rs_exp =
+  let fault name = RsApp (RsId name, [], [ RsLit RsLitUnit ]) in
+  let case variant name = RsPexp (RsPatApp (RsPatId variant, [ RsPatWildcard ]), fault name) in
+  RsMatch
+    ( access_type
+    , [ case "InstructionFetch" "E_Fetch_Access_Fault"
+      ; case "Read" "E_Load_Access_Fault"
+      ; RsPexp (RsPatWildcard, fault "E_SAMO_Access_Fault")
+      ] )
+;;
+
 (* TODO: This list is probably incomplete and we might want to add extra fields in the future *)
 let native_func_transform_exp (ctx : context) (exp : rs_exp) : rs_exp =
   match exp with
@@ -782,6 +852,31 @@ let native_func_transform_exp (ctx : context) (exp : rs_exp) : rs_exp =
   | RsApp (RsId "gt_int", gens, [ e1; e2 ]) -> RsBinop (e1, RsBinopGt, e2)
   | RsApp (RsId "internal_error", gens, [ file; line; message ]) ->
     RsApp (RsId "panic!", [], [ RsLit (RsLitStr "{}, l {}: {}"); file; line; message ])
+  | RsApp (RsId ("__read_mem" | "read_mem"), gens, [ _kind; _addr_size; addr; width ]) ->
+    memory_method_app "read_mem" [ addr; width ]
+  | RsApp
+      (RsId ("__write_mem" | "write_mem"), gens, [ _kind; _addr_size; addr; width; data ])
+    -> core_method_app "write_mem" [ addr; width; data ]
+  | RsApp (RsId "within_phys_mem", gens, [ paddr; width ]) ->
+    let unwrap_paddr = RsPatApp (RsPatId "Physaddr", [ RsPatId "addr" ]) in
+    RsMatch
+      (paddr, [ RsPexp (unwrap_paddr, memory_method_app "is_mapped" [ RsId "addr"; width ]) ])
+  | RsApp (RsId "ext_check_phys_mem_read", gens, access_type :: paddr :: width :: _) ->
+    memory_check paddr width (access_fault access_type)
+  | RsApp (RsId "ext_check_phys_mem_write", gens, _kind :: paddr :: width :: _) ->
+    memory_check paddr width (RsApp (RsId "E_SAMO_Access_Fault", [], [ RsLit RsLitUnit ]))
+  | RsApp (RsId "translateAddr", gens, [ vaddr; access_type ]) ->
+    core_method_app "translate_addr" [ vaddr; access_type ]
+  | RsApp (RsId "csr_id_write_callback", gens, [ csr; value ]) ->
+    platform_method_app "csr_id_write" [ csr; value ]
+  | RsApp
+      ( RsId "csr_name_write_callback"
+      , gens
+      , [ RsApp (RsId "String::from", _, [ (RsLit (RsLitStr _) as name) ]); value ] ) ->
+    (* CSR names are literals, passed as static strings to avoid an allocation per write *)
+    platform_method_app "csr_name_write" [ name; value ]
+  | RsApp (RsId "sail_branch_announce", gens, [ size; target ]) ->
+    platform_method_app "branch_announce" [ size; target ]
   | RsApp (RsId id, gens, _) when SSet.mem id unsupported_fun -> RsLit RsLitUnit
   | _ -> exp
 ;;
@@ -1060,6 +1155,9 @@ let expr_type_hoister =
 
 let exp_virt_ctx_usage (ctx : context) (exp : rs_exp) : rs_exp =
   match exp with
+  | RsApp (RsId fn, _, _) when SSet.mem fn memory_fun || SSet.mem fn trace_fun ->
+    ctx.uses_sail_ctx <- true;
+    exp
   | RsApp (RsId fn, _, _) ->
     (match ctx_fun fn ctx with
      | Some fn when fn.use_sail_ctx ->
@@ -1201,6 +1299,69 @@ let fix_generic_type_func (ctx : context) (func : rs_fn) : rs_fn =
 
 let fix_generic_type : func_transform = { func = fix_generic_type_func }
 
+(* ——————————————————————————————— Step Outcome ——————————————————————————————— *)
+(* The `try_step` function of the specification only reports whether the      *)
+(* hart is waiting. The Rust model returns the outcome of the step instead, so *)
+(* that callers can observe retirements, traps and fetch failures.             *)
+(* —————————————————————————————————————————————————————————————————————————— *)
+
+(* The variable holding the outcome of the step in `try_step`. *)
+let step_outcome_var = "step_val"
+
+let return_step_outcome_func (ctx : context) (func : rs_fn) : rs_fn =
+  (* Replace the returned value, which is always the last expression. *)
+  let rec return_step_outcome (exp : rs_exp) : rs_exp =
+    match exp with
+    | RsLet (pat, value, next) -> RsLet (pat, value, return_step_outcome next)
+    | RsBlock exps ->
+      (match List.rev exps with
+       | last :: rest -> RsBlock (List.rev (return_step_outcome last :: rest))
+       | [] -> RsId step_outcome_var)
+    | _ -> RsId step_outcome_var
+  in
+  match func.name with
+  | "try_step" ->
+    let signature = { func.signature with ret = RsTypId "Step" } in
+    { func with signature; body = return_step_outcome func.body }
+  | _ -> func
+;;
+
+let return_step_outcome : func_transform = { func = return_step_outcome_func }
+
+(* ——————————————————————— Platform Notifications ——————————————————————————— *)
+(* The specification does not notify register writes nor executed           *)
+(* instructions. When the core has a platform, the functions writing        *)
+(* registers and executing instructions are modified to notify the platform *)
+(* with their arguments before running.                                     *)
+(* —————————————————————————————————————————————————————————————————————————— *)
+
+(* Functions of the specification and the platform method they notify. *)
+let platform_notify_fun : string SMap.t =
+  SMap.of_list
+    [ "wX_bits", "x_write"
+    ; "wF_bits", "f_write"
+    ; "wV_bits", "v_write"
+    ; "execute", "instr_announce"
+    ]
+;;
+
+let platform_notify_func (ctx : context) (func : rs_fn) : rs_fn =
+  let rec arg_exp (pat : rs_pat) : rs_exp option =
+    match pat with
+    | RsPatId id -> Some (RsId id)
+    | RsPatType (_, pat) -> arg_exp pat
+    | _ -> None
+  in
+  let args = List.filter_map arg_exp func.args in
+  match SMap.find_opt func.name platform_notify_fun with
+  | Some notify
+    when uses_platform ctx.call_set && List.length args = List.length func.args ->
+    { func with body = RsBlock [ platform_method_app notify args; func.body ] }
+  | _ -> func
+;;
+
+let platform_notify : func_transform = { func = platform_notify_func }
+
 (* ————————————————————————— Link Generics to Args —————————————————————————— *)
 (* In some cases Sail generics are determined by the value of an argument.    *)
 (* In Rust we emulate that by using both a generic and an argument.           *)
@@ -1432,7 +1593,7 @@ let transform_basic_types_pexp (ctx : context) (pexp : rs_pexp) : rs_pexp = pexp
 
 let transform_basic_types_type (ctx : context) (typ : rs_type) : rs_type =
   match typ with
-  | RsTypId "string" -> RsTypId "&\'static str"
+  | RsTypId "string" -> RsTypId "String"
   | RsTypId "int" -> int_typ
   | RsTypId "bit" -> bool_typ
   (* TODO: Is this transformation legal? Should we add an assertion at some place in the code? *)
@@ -1522,6 +1683,337 @@ let sail_context_arg_inserter : expr_type_transform =
   }
 ;;
 
+(* ———————————————————————— Monomorphisation  ————————————————————————— *)
+(* The page table walker is generic over the width of virtual addresses,    *)
+(* and the sizes of its bitvectors are expressions of that width (e.g.      *)
+(* `bits('v - 12)`), which require generic const expressions in Rust.       *)
+(* This pass specializes the types and functions of the walker for each     *)
+(* width supported on RV64, for instance `pt_walk` becomes `pt_walk_39`.    *)
+(* Callers passing the width as a variable are split with one branch per    *)
+(* width.                                                                   *)
+(* —————————————————————————————————————————————————————————————————————————— *)
+
+(* Types generic over the width of virtual addresses *)
+let mono_types : SSet.t =
+  SSet.of_list [ "PTW_Output"; "PTW_Result"; "pte_bits"; "ppn_bits"; "vpn_bits" ]
+;;
+
+let mono_name (name : string) (width : int) : string = Printf.sprintf "%s_%d" name width
+
+let find_index (pred : 'a -> bool) (lst : 'a list) : int option =
+  let rec find idx lst =
+    match lst with
+    | head :: tail -> if pred head then Some idx else find (idx + 1) tail
+    | [] -> None
+  in
+  find 0 lst
+;;
+
+(* Evaluates constant expressions, such as the sizes of specialized bitvectors *)
+let rec eval_num (exp : rs_exp) : Big_int.num option =
+  let binop op e1 e2 =
+    match eval_num e1, eval_num e2 with
+    | Some n1, Some n2 -> Some (op n1 n2)
+    | _ -> None
+  in
+  match exp with
+  | RsLit (RsLitNum n) -> Some n
+  | RsBlock [ exp ] | RsAs (exp, _) -> eval_num exp
+  | RsBinop (e1, RsBinopAdd, e2) -> binop Big_int.add e1 e2
+  | RsBinop (e1, RsBinopSub, e2) -> binop Big_int.sub e1 e2
+  | RsBinop (e1, RsBinopMult, e2) -> binop Big_int.mul e1 e2
+  | RsIf (cond, exp_true, exp_false) ->
+    (match eval_bool cond with
+     | Some true -> eval_num exp_true
+     | Some false -> eval_num exp_false
+     | None -> None)
+  | _ -> None
+
+and eval_bool (exp : rs_exp) : bool option =
+  let compare op e1 e2 =
+    match eval_num e1, eval_num e2 with
+    | Some n1, Some n2 -> Some (op (Big_int.compare n1 n2) 0)
+    | _ -> None
+  in
+  match exp with
+  | RsLit RsLitTrue -> Some true
+  | RsLit RsLitFalse -> Some false
+  | RsBinop (e1, RsBinopEq, e2) -> compare ( = ) e1 e2
+  | RsBinop (e1, RsBinopNeq, e2) -> compare ( <> ) e1 e2
+  | RsBinop (e1, RsBinopLAnd, e2) ->
+    (match eval_bool e1, eval_bool e2 with
+     | Some b1, Some b2 -> Some (b1 && b2)
+     | _ -> None)
+  | RsBinop (e1, RsBinopLOr, e2) ->
+    (match eval_bool e1, eval_bool e2 with
+     | Some b1, Some b2 -> Some (b1 || b2)
+     | _ -> None)
+  | _ -> None
+;;
+
+(* Substitutes variables (or generics) by a constant, in expressions and types *)
+let subst_const (names : string list) (value : int) : expr_type_transform =
+  let subst_exp ctx exp =
+    match exp with
+    | RsId id when List.mem id names -> mk_num value
+    | _ -> exp
+  in
+  let exp_transform =
+    { exp = subst_exp
+    ; lexp = id_lexp
+    ; pexp = id_pexp
+    ; typ = id_typ
+    ; pat = id_pat
+    ; obj = id_obj
+    }
+  in
+  let subst_param ctx param =
+    match param with
+    | RsTypParamTyp (RsTypId id | RsTypGeneric id) when List.mem id names ->
+      RsTypParamNum (mk_num value)
+    | RsTypParamNum n ->
+      let n = transform_exp exp_transform ctx n in
+      (match eval_num n with
+       | Some n -> RsTypParamNum (mk_big_num n)
+       | None -> RsTypParamNum n)
+    | _ -> param
+  in
+  let subst_typ ctx typ =
+    match typ with
+    | RsTypGenericParam (name, params) ->
+      RsTypGenericParam (name, List.map (subst_param ctx) params)
+    | RsTypArray (typ, size) -> RsTypArray (subst_param ctx typ, subst_param ctx size)
+    | RsTypOption param -> RsTypOption (subst_param ctx param)
+    | _ -> typ
+  in
+  { exp_transform with typ = subst_typ }
+;;
+
+(* Returns the width generic of the first width-generic type, if any *)
+let rec mono_generic_of_type (typ : rs_type) : string option =
+  match typ with
+  | RsTypGenericParam (name, RsTypParamTyp (RsTypId gen) :: _)
+    when SSet.mem name mono_types -> Some gen
+  | RsTypGenericParam (_, params) -> List.find_map mono_generic_of_param params
+  | RsTypTuple typs -> List.find_map mono_generic_of_type typs
+  | RsTypArray (param, _) | RsTypOption param -> mono_generic_of_param param
+  | _ -> None
+
+and mono_generic_of_param (param : rs_type_param) : string option =
+  match param with
+  | RsTypParamTyp typ -> mono_generic_of_type typ
+  | RsTypParamNum _ -> None
+;;
+
+(* The functions whose signature depends on width-generic types, with their width generic *)
+let mono_fns (RsProg objs : rs_program) : string SMap.t =
+  let add_fn fns obj =
+    match obj with
+    | RsFn fn ->
+      (match
+         List.find_map mono_generic_of_type (fn.signature.ret :: fn.signature.args)
+       with
+       | Some gen -> SMap.add fn.name gen fns
+       | None -> fns)
+    | _ -> fns
+  in
+  List.fold_left add_fn SMap.empty objs
+;;
+
+(* Renames width-generic types and functions to their specialization for `width`.
+   Width-generic functions only have the width as generic parameter. *)
+let mono_rename (fns : string SMap.t) (width : int) : expr_type_transform =
+  let rename_path id =
+    match String.index_opt id ':' with
+    | Some idx when SSet.mem (String.sub id 0 idx) mono_types ->
+      mono_name (String.sub id 0 idx) width ^ String.sub id idx (String.length id - idx)
+    | _ -> id
+  in
+  let rename_exp ctx exp =
+    match exp with
+    | RsId id -> RsId (rename_path id)
+    | RsApp (RsId id, _, args) when SMap.mem id fns ->
+      RsApp (RsId (mono_name id width), [], args)
+    | RsApp (RsId id, generics, args) -> RsApp (RsId (rename_path id), generics, args)
+    | _ -> exp
+  in
+  let rename_pat ctx pat =
+    match pat with
+    | RsPatId id -> RsPatId (rename_path id)
+    | _ -> pat
+  in
+  let rename_typ ctx typ =
+    match typ with
+    | (RsTypId name | RsTypGenericParam (name, _)) when SSet.mem name mono_types ->
+      RsTypId (mono_name name width)
+    | _ -> typ
+  in
+  { exp = rename_exp
+  ; lexp = id_lexp
+  ; pexp = id_pexp
+  ; typ = rename_typ
+  ; pat = rename_pat
+  ; obj = id_obj
+  }
+;;
+
+(* Returns the position and value of the width argument of a call to a width-generic function *)
+let mono_width_arg (fns : string SMap.t) (ctx : context) (exp : rs_exp)
+  : (int * rs_exp) option
+  =
+  match exp with
+  | RsApp (RsId id, _, args) when SMap.mem id fns ->
+    let gen = SMap.find id fns in
+    let is_width typ =
+      match typ with
+      | RsTypGenericParam (("atom" | "implicit"), [ RsTypParamTyp (RsTypId g) ]) -> g = gen
+      | _ -> false
+    in
+    (* The core context is inserted before the arguments of the specification *)
+    let offset =
+      match ctx_fun id ctx with
+      | Some fn when fn.use_sail_ctx -> 1
+      | _ -> 0
+    in
+    let width_idx (typ : rs_fn_type) = find_index is_width typ.args in
+    (match Option.bind (ctx_fun_type id ctx) width_idx with
+     | Some idx when idx + offset < List.length args ->
+       Some (idx + offset, List.nth args (idx + offset))
+     | _ -> None)
+  | _ -> None
+;;
+
+(* Returns the variables used as width when calling width-generic functions *)
+let mono_width_vars (fns : string SMap.t) (ctx : context) (exp : rs_exp) : SSet.t =
+  let vars = ref SSet.empty in
+  let collect ctx exp =
+    (match mono_width_arg fns ctx exp with
+     | Some (_, RsId var) -> vars := SSet.add var !vars
+     | _ -> ());
+    exp
+  in
+  let collector =
+    { exp = collect; lexp = id_lexp; pexp = id_pexp; typ = id_typ; pat = id_pat; obj = id_obj }
+  in
+  ignore (transform_exp collector ctx exp);
+  !vars
+;;
+
+let calls_mono_fn (fns : string SMap.t) (ctx : context) (fn : rs_fn) : bool =
+  let found = ref false in
+  let check ctx exp =
+    (match exp with
+     | RsApp (RsId id, _, _) when SMap.mem id fns -> found := true
+     | _ -> ());
+    exp
+  in
+  let checker =
+    { exp = check; lexp = id_lexp; pexp = id_pexp; typ = id_typ; pat = id_pat; obj = id_obj }
+  in
+  ignore (transform_fn checker ctx fn);
+  !found
+;;
+
+let mono_split (fns : string SMap.t) : expr_type_transform =
+  let split_on (scrutinee : rs_exp) (branch : int -> rs_exp) : rs_exp =
+    let branches =
+      List.map
+        (fun width -> RsPexp (RsPatLit (RsLitNum (Big_int.of_int width)), branch width))
+        Core_config.sv_widths
+    in
+    let unreachable =
+      RsPexp
+        (RsPatWildcard, RsApp (RsId "panic!", [], [ RsLit (RsLitStr "Unreachable code") ]))
+    in
+    RsMatch (scrutinee, branches @ [ unreachable ])
+  in
+  let split ctx exp =
+    match exp with
+    (* The width is bound to a variable: specialize the rest of the block for each width. *)
+    | RsLet (pat, value, next) ->
+      let vars = SSet.inter (ids_of_pat pat) (mono_width_vars fns ctx next) in
+      (match SSet.elements vars with
+       | var :: _ ->
+         let specialize width =
+           transform_exp (subst_const [ var; String.uppercase_ascii var ] width) ctx next
+         in
+         RsLet (pat, value, split_on (RsId var) specialize)
+       | [] -> exp)
+    | RsApp (RsId id, _, args) ->
+      (match mono_width_arg fns ctx exp with
+       | Some (idx, width) ->
+         let call width =
+           let args = List.mapi (fun i arg -> if i = idx then mk_num width else arg) args in
+           RsApp (RsId (mono_name id width), [], args)
+         in
+         (match eval_num width with
+          | Some width -> call (Big_int.to_int width)
+          | None -> split_on width call)
+       | None -> exp)
+    | _ -> exp
+  in
+  { exp = split; lexp = id_lexp; pexp = id_pexp; typ = id_typ; pat = id_pat; obj = id_obj }
+;;
+
+let monomorphise_obj (fns : string SMap.t) (ctx : context) (obj : rs_obj) : rs_obj list =
+  let without gen generics = List.filter (fun g -> g <> RsGenConst (gen, "i128")) generics in
+  let width_generic generics =
+    List.find_map
+      (fun g ->
+         match g with
+         | RsGenConst (g, _) -> Some g
+         | RsGenTyp _ -> None)
+      generics
+  in
+  let specialize gen obj =
+    List.map
+      (fun width ->
+         obj width
+         |> transform_obj (subst_const [ gen ] width) ctx
+         |> transform_obj (mono_rename fns width) ctx)
+      Core_config.sv_widths
+  in
+  match obj with
+  | RsFn fn when SMap.mem fn.name fns ->
+    let gen = SMap.find fn.name fns in
+    let generics = without gen fn.signature.generics in
+    specialize gen (fun width ->
+      RsFn
+        { fn with
+          name = mono_name fn.name width
+        ; signature = { fn.signature with generics }
+        })
+  | RsFn fn when calls_mono_fn fns ctx fn -> [ transform_obj (mono_split fns) ctx obj ]
+  | RsStruct s when SSet.mem s.name mono_types ->
+    (match width_generic s.generics with
+     | Some gen ->
+       let generics = without gen s.generics in
+       specialize gen (fun width ->
+         RsStruct { s with name = mono_name s.name width; generics })
+     | None -> [ obj ])
+  | RsEnum enum when SSet.mem enum.name mono_types ->
+    (match width_generic enum.generics with
+     | Some gen ->
+       let generics = without gen enum.generics in
+       specialize gen (fun width ->
+         RsEnum { enum with name = mono_name enum.name width; generics })
+     | None -> [ obj ])
+  | RsAlias alias when SSet.mem alias.new_typ mono_types ->
+    (match width_generic alias.generics with
+     | Some gen ->
+       let generics = without gen alias.generics in
+       specialize gen (fun width ->
+         RsAlias { alias with new_typ = mono_name alias.new_typ width; generics })
+     | None -> [ obj ])
+  | _ -> [ obj ]
+;;
+
+let monomorphise (ctx : context) (program : rs_program) : rs_program =
+  let fns = mono_fns program in
+  let (RsProg objs) = program in
+  RsProg (List.concat_map (monomorphise_obj fns ctx) objs)
+;;
+
 (* TODO: This is a very (almost useless) basic dead code remover only for our use case. Extend it in the future *)
 (* ———————————————————————— Dead code remover  ————————————————————————— *)
 
@@ -1566,17 +2058,17 @@ let dead_code_remover : expr_type_transform =
 let unsupported_obj : SSet.t =
   SSet.of_list
     [ (* Used only for side effects, not necessary in the Rust back-end *)
+      "csr_full_write_callback"
+    ; "long_csr_write_callback"
+    ; (* Replaced by calls to the platform attached to the core *)
       "csr_name_write_callback"
     ; "csr_id_write_callback"
-    ; "csr_full_write_callback"
-    ; "long_csr_write_callback"
+    ; (* Replaced by calls to the memory backend *)
+      "within_phys_mem"
+    ; "ext_check_phys_mem_read"
+    ; "ext_check_phys_mem_write"
     ; (* Depend on const generic exprs, would require monomorphisation. *)
       "Mem_write_request"
-    ; "PTW_Output"
-    ; "PTW_Result"
-    ; "pte_bits"
-    ; "ppn_bits"
-    ; "vpn_bits"
     ]
 ;;
 
@@ -1651,6 +2143,8 @@ let transform (rust_program : rs_program) (ctx : context) : rs_program =
   let rust_program = rust_transform_func enum_arg_namespace ctx rust_program in
   let rust_program = rust_transform_func fix_scattered_func ctx rust_program in
   let rust_program = rust_transform_func fix_generic_type ctx rust_program in
+  let rust_program = rust_transform_func return_step_outcome ctx rust_program in
+  let rust_program = rust_transform_func platform_notify ctx rust_program in
   let rust_program = rust_transform_expr link_generics_to_args ctx rust_program in
   let rust_program = rust_transform_expr enum_binder ctx rust_program in
   let rust_program = rust_remove_type_bits rust_program in
@@ -1659,6 +2153,7 @@ let transform (rust_program : rs_program) (ctx : context) : rs_program =
   let rust_program = rust_transform_expr transform_basic_types ctx rust_program in
   let rust_program = rust_transform_expr add_wildcard_match ctx rust_program in
   let rust_program = rust_transform_expr sail_context_arg_inserter ctx rust_program in
+  let rust_program = monomorphise ctx rust_program in
   let rust_program = rust_transform_expr expr_type_hoister ctx rust_program in
   let rust_program = rust_transform_expr expr_type_operator_rewriter ctx rust_program in
   let rust_program = rust_transform_func const_fn_rewriter ctx rust_program in
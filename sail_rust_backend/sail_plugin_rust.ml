@@ -12,9 +12,14 @@ let opt_debug = ref false
 let opt_branch_coverage = ref None
 let opt_no_mangle = ref false
 let opt_preserve_types = ref IdSet.empty
+let opt_vlen_exp = ref None
 
 let rust_options =
-  [ Flag.create ~prefix:[ "rust" ] "debug", Arg.Set opt_debug, "enable debug logging" ]
+  [ Flag.create ~prefix:[ "rust" ] "debug", Arg.Set opt_debug, "enable debug logging"
+  ; ( Flag.create ~prefix:[ "rust" ] ~arg:"n" "vlen_exp"
+    , Arg.Int (fun n -> opt_vlen_exp := Some n)
+    , "length of vector registers as a power of two (default: 3)" )
+  ]
 ;;
 
 let collect_rust_name_info ast =
@@ -91,7 +96,7 @@ let rust_target out_file { ast; effect_info; env; _ } =
     | Some f -> f
     | None -> "out.rs"
   in
-  let impl = Codegen.compile_ast env effect_info ast in
+  let impl = Codegen.compile_ast !opt_vlen_exp env effect_info ast in
   let impl_out = Util.open_output_with_check basename in
   output_string impl_out.channel impl;
   flush impl_out.channel;
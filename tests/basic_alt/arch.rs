@@ -9,14 +9,17 @@ use softcore_prelude::*;
 /// 
 /// The raw functions translated directly from the specification are available in the `raw` module, whereas higher-level wrappers are implemented as methods on the [Core] struct directly.
 #[derive(Eq, PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub struct Core {
     pub PC: xlenbits,
     pub nextPC: xlenbits,
+    #[cfg_attr(feature = "serde", serde(with = "softcore_prelude::serde_array"))]
     pub Xs: [xlenbits; (32 as usize)],
     pub config: Config,
 }
 
 #[derive(Eq, PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub struct Config {
 
 }
@@ -31,7 +34,7 @@ pub fn _reset_all_registers() {
 /// EXTZ
 /// 
 /// Generated from the Sail sources at `tests/basic_alt/arch.sail` L7.
-pub fn EXTZ<const N: i128, const M: i128>(m: i128, v: BitVector<N>) -> BitVector<M> {
+pub fn EXTZ<const N: i128, const M: i128>(m: i128, v: BitVector<N>) -> BitVector<M> where Width<N>: SupportedWidth, Width<M>: SupportedWidth {
     v.zero_extend()
 }
 
@@ -69,6 +72,7 @@ pub fn wX(core_ctx: &mut Core, r: BitVector<5>, v: BitVector<64>) {
 /// 
 /// Generated from the Sail sources at `tests/basic_alt/arch.sail` L61.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum iop {
     RISCV_ADDI,
     RISCV_SLTI,
@@ -82,6 +86,7 @@ pub enum iop {
 /// 
 /// Generated from the Sail sources at `tests/basic_alt/arch.sail` L63.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum ast {
     ITYPE((BitVector<12>, BitVector<5>, BitVector<5>, iop)),
     LOAD((BitVector<12>, BitVector<5>, BitVector<5>))
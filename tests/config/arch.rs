@@ -9,28 +9,33 @@ use softcore_prelude::*;
 /// 
 /// The raw functions translated directly from the specification are available in the `raw` module, whereas higher-level wrappers are implemented as methods on the [Core] struct directly.
 #[derive(Eq, PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub struct Core {
     pub PC: xlenbits,
     pub config: Config,
 }
 
 #[derive(Eq, PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub struct Config {
     pub extensions: ConfigExtensions,
 }
 
 #[derive(Eq, PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub struct ConfigExtensions {
     pub S: ConfigS,
     pub U: ConfigU,
 }
 
 #[derive(Eq, PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub struct ConfigS {
     pub supported: bool,
 }
 
 #[derive(Eq, PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub struct ConfigU {
     pub supported: bool,
 }
@@ -52,6 +57,7 @@ pub type xlenbits = BitVector<xlen>;
 /// 
 /// Generated from the Sail sources at `tests/config/arch.sail` L12.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum extension {
     Ext_U,
     Ext_S
@@ -74,6 +80,7 @@ pub type regbits = BitVector<5>;
 /// 
 /// Generated from the Sail sources at `tests/config/arch.sail` L32.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum ast {
     TEST(())
 }
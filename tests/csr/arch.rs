@@ -9,17 +9,20 @@ use softcore_prelude::*;
 /// 
 /// The raw functions translated directly from the specification are available in the `raw` module, whereas higher-level wrappers are implemented as methods on the [Core] struct directly.
 #[derive(Eq, PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub struct Core {
     pub PC: xlenbits,
     pub nextPC: xlenbits,
     pub mscratch: xlenbits,
     pub sscratch: xlenbits,
     pub cur_privilege: Privilege,
+    #[cfg_attr(feature = "serde", serde(with = "softcore_prelude::serde_array"))]
     pub Xs: [xlenbits; (32 as usize)],
     pub config: Config,
 }
 
 #[derive(Eq, PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub struct Config {
 
 }
@@ -34,7 +37,7 @@ pub fn _reset_all_registers() {
 /// EXTZ
 /// 
 /// Generated from the Sail sources at `tests/csr/arch.sail` L7.
-pub fn EXTZ<const N: i128, const M: i128>(m: i128, v: BitVector<N>) -> BitVector<M> {
+pub fn EXTZ<const N: i128, const M: i128>(m: i128, v: BitVector<N>) -> BitVector<M> where Width<N>: SupportedWidth, Width<M>: SupportedWidth {
     v.zero_extend()
 }
 
@@ -50,6 +53,7 @@ pub type priv_level = BitVector<2>;
 /// 
 /// Generated from the Sail sources at `tests/csr/arch.sail` L34.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum Privilege {
     User,
     Supervisor,
@@ -111,6 +115,7 @@ pub fn bool_bits_backwards_matches(arg_hashtag_: BitVector<1>) -> bool {
 /// 
 /// Generated from the Sail sources at `tests/csr/arch.sail` L99.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum iop {
     RISCV_ADDI,
     RISCV_SLTI,
@@ -124,6 +129,7 @@ pub enum iop {
 /// 
 /// Generated from the Sail sources at `tests/csr/arch.sail` L100.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum csrop {
     CSRRW,
     CSRRS,
@@ -134,6 +140,7 @@ pub enum csrop {
 /// 
 /// Generated from the Sail sources at `tests/csr/arch.sail` L101.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum Retired {
     RETIRE_SUCCESS,
     RETIRE_FAIL
@@ -143,6 +150,7 @@ pub enum Retired {
 /// 
 /// Generated from the Sail sources at `tests/csr/arch.sail` L103.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum ast {
     ITYPE((BitVector<12>, regidx, regidx, iop)),
     CSR((BitVector<12>, regidx, regidx, bool, csrop))
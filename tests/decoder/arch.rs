@@ -9,11 +9,13 @@ use softcore_prelude::*;
 /// 
 /// The raw functions translated directly from the specification are available in the `raw` module, whereas higher-level wrappers are implemented as methods on the [Core] struct directly.
 #[derive(Eq, PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub struct Core {
     pub config: Config,
 }
 
 #[derive(Eq, PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub struct Config {
 
 }
@@ -37,6 +39,7 @@ pub type priv_level = BitVector<2>;
 /// 
 /// Generated from the Sail sources at `tests/decoder/arch.sail` L34.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum Privilege {
     User,
     Supervisor,
@@ -76,6 +79,7 @@ pub fn bool_bits_backwards_matches(arg_hashtag_: BitVector<1>) -> bool {
 /// 
 /// Generated from the Sail sources at `tests/decoder/arch.sail` L58.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum iop {
     RISCV_ADDI,
     RISCV_SLTI,
@@ -89,6 +93,7 @@ pub enum iop {
 /// 
 /// Generated from the Sail sources at `tests/decoder/arch.sail` L59.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum csrop {
     CSRRW,
     CSRRS,
@@ -99,6 +104,7 @@ pub enum csrop {
 /// 
 /// Generated from the Sail sources at `tests/decoder/arch.sail` L60.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum Retired {
     RETIRE_SUCCESS,
     RETIRE_FAIL
@@ -108,6 +114,7 @@ pub enum Retired {
 /// 
 /// Generated from the Sail sources at `tests/decoder/arch.sail` L62.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum ast {
     CSR((BitVector<12>, regidx, regidx, bool, csrop)),
     MRET(()),
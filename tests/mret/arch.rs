@@ -9,6 +9,7 @@ use softcore_prelude::*;
 /// 
 /// The raw functions translated directly from the specification are available in the `raw` module, whereas higher-level wrappers are implemented as methods on the [Core] struct directly.
 #[derive(Eq, PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub struct Core {
     pub PC: xlenbits,
     pub nextPC: xlenbits,
@@ -17,11 +18,13 @@ pub struct Core {
     pub uepc: xlenbits,
     pub mstatus: Mstatus,
     pub cur_privilege: Privilege,
+    #[cfg_attr(feature = "serde", serde(with = "softcore_prelude::serde_array"))]
     pub Xs: [xlenbits; (32 as usize)],
     pub config: Config,
 }
 
 #[derive(Eq, PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub struct Config {
 
 }
@@ -45,6 +48,7 @@ pub type priv_level = BitVector<2>;
 /// 
 /// Generated from the Sail sources at `tests/mret/arch.sail` L40.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum Privilege {
     User,
     Supervisor,
@@ -78,7 +82,7 @@ pub fn privLevel_of_bits(p: BitVector<2>) -> Privilege {
         b__0 if {(b__0 == BitVector::<2>::new(0b00))} => {Privilege::User}
         b__1 if {(b__1 == BitVector::<2>::new(0b01))} => {Privilege::Supervisor}
         b__2 if {(b__2 == BitVector::<2>::new(0b11))} => {Privilege::Machine}
-        _ => {not_implemented("Invalid privilege level")}
+        _ => {not_implemented(String::from("Invalid privilege level"))}
         _ => {panic!("Unreachable code")}
     }
 }
@@ -100,6 +104,7 @@ pub type csreg = BitVector<12>;
 /// 
 /// Generated from the Sail sources at `tests/mret/arch.sail` L81-105.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub struct Mstatus {
     pub bits: BitVector<64>,
 }
@@ -206,6 +211,7 @@ pub fn exception_handler(core_ctx: &mut Core, cur_priv: Privilege, pc: BitVector
 /// 
 /// Generated from the Sail sources at `tests/mret/arch.sail` L188.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum Retired {
     RETIRE_SUCCESS,
     RETIRE_FAIL
@@ -215,6 +221,7 @@ pub enum Retired {
 /// 
 /// Generated from the Sail sources at `tests/mret/arch.sail` L190.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum ast {
     MRET(())
 }
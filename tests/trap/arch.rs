@@ -9,6 +9,7 @@ use softcore_prelude::*;
 /// 
 /// The raw functions translated directly from the specification are available in the `raw` module, whereas higher-level wrappers are implemented as methods on the [Core] struct directly.
 #[derive(Eq, PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub struct Core {
     pub PC: xlenbits,
     pub nextPC: xlenbits,
@@ -29,11 +30,13 @@ pub struct Core {
     pub stvec: Mtvec,
     pub utvec: Mtvec,
     pub cur_privilege: Privilege,
+    #[cfg_attr(feature = "serde", serde(with = "softcore_prelude::serde_array"))]
     pub Xs: [xlenbits; (32 as usize)],
     pub config: Config,
 }
 
 #[derive(Eq, PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub struct Config {
 
 }
@@ -77,7 +80,7 @@ pub fn bool_to_bits(x: bool) -> BitVector<1> {
 /// (operator <_u)
 /// 
 /// Generated from the Sail sources at `tests/trap/arch.sail` L45.
-pub fn _operator_smaller_u_<const N: i128>(x: BitVector<N>, y: BitVector<N>) -> bool {
+pub fn _operator_smaller_u_<const N: i128>(x: BitVector<N>, y: BitVector<N>) -> bool where Width<N>: SupportedWidth {
     (x.unsigned() < y.unsigned())
 }
 
@@ -93,6 +96,7 @@ pub type priv_level = BitVector<2>;
 /// 
 /// Generated from the Sail sources at `tests/trap/arch.sail` L56.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum Privilege {
     User,
     Supervisor,
@@ -129,6 +133,7 @@ pub const fn haveUsrMode(unit_arg: ()) -> bool {
 /// 
 /// Generated from the Sail sources at `tests/trap/arch.sail` L73-76.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum exception {
     Error_internal_error(())
 }
@@ -143,6 +148,7 @@ pub type csreg = BitVector<12>;
 /// 
 /// Generated from the Sail sources at `tests/trap/arch.sail` L99-114.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub struct Medeleg {
     pub bits: BitVector<64>,
 }
@@ -151,6 +157,7 @@ pub struct Medeleg {
 /// 
 /// Generated from the Sail sources at `tests/trap/arch.sail` L117-120.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub struct Mcause {
     pub bits: BitVector<64>,
 }
@@ -159,6 +166,7 @@ pub struct Mcause {
 /// 
 /// Generated from the Sail sources at `tests/trap/arch.sail` L125-149.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub struct Mstatus {
     pub bits: BitVector<64>,
 }
@@ -167,6 +175,7 @@ pub struct Mstatus {
 /// 
 /// Generated from the Sail sources at `tests/trap/arch.sail` L152-155.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub struct Mtvec {
     pub bits: BitVector<64>,
 }
@@ -269,6 +278,7 @@ pub fn bool_bits_backwards_matches(arg_hashtag_: BitVector<1>) -> bool {
 /// 
 /// Generated from the Sail sources at `tests/trap/arch.sail` L193-210.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum ExceptionType {
     E_Fetch_Addr_Align(()),
     E_Fetch_Access_Fault(()),
@@ -344,6 +354,7 @@ pub fn exceptionType_to_bits(e: ExceptionType) -> BitVector<8> {
 /// 
 /// Generated from the Sail sources at `tests/trap/arch.sail` L256-259.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub struct sync_exception {
     pub trap: ExceptionType,
     pub excinfo: Option<xlenbits>,
@@ -353,6 +364,7 @@ pub struct sync_exception {
 /// 
 /// Generated from the Sail sources at `tests/trap/arch.sail` L262-264.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum ctl_result {
     CTL_TRAP(sync_exception)
 }
@@ -363,6 +375,7 @@ pub type tv_mode = BitVector<2>;
 /// 
 /// Generated from the Sail sources at `tests/trap/arch.sail` L267.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum TrapVectorMode {
     TV_Direct,
     TV_Vector,
@@ -531,6 +544,7 @@ pub fn exception_delegatee(core_ctx: &mut Core, e: ExceptionType, p: Privilege)
 /// 
 /// Generated from the Sail sources at `tests/trap/arch.sail` L400.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum iop {
     RISCV_ADDI,
     RISCV_SLTI,
@@ -544,6 +558,7 @@ pub enum iop {
 /// 
 /// Generated from the Sail sources at `tests/trap/arch.sail` L401.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum csrop {
     CSRRW,
     CSRRS,
@@ -554,6 +569,7 @@ pub enum csrop {
 /// 
 /// Generated from the Sail sources at `tests/trap/arch.sail` L402.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum Retired {
     RETIRE_SUCCESS,
     RETIRE_FAIL
@@ -563,6 +579,7 @@ pub enum Retired {
 /// 
 /// Generated from the Sail sources at `tests/trap/arch.sail` L404.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum ast {
     ITYPE((BitVector<12>, regidx, regidx, iop)),
     CSR((BitVector<12>, regidx, regidx, bool, csrop))
@@ -9,11 +9,13 @@ use softcore_prelude::*;
 /// 
 /// The raw functions translated directly from the specification are available in the `raw` module, whereas higher-level wrappers are implemented as methods on the [Core] struct directly.
 #[derive(Eq, PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub struct Core {
     pub config: Config,
 }
 
 #[derive(Eq, PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub struct Config {
     pub unknown_at_compile_time: i128,
 }
@@ -35,6 +37,7 @@ pub const xlen: i128 = 64;
 /// 
 /// Generated from the Sail sources at `tests/types/arch.sail` L15-23.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum ExceptionType {
     E_Fetch_Addr_Align(()),
     E_Fetch_Access_Fault(()),
@@ -47,6 +50,7 @@ pub enum ExceptionType {
 /// 
 /// Generated from the Sail sources at `tests/types/arch.sail` L25.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum physaddr {
     Physaddr(xlenbits)
 }
@@ -104,6 +108,7 @@ pub fn handle_empty(unit_arg: ()) {
 /// 
 /// Generated from the Sail sources at `tests/types/arch.sail` L59.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum ast {
     TEST(())
 }
@@ -111,14 +116,14 @@ pub enum ast {
 /// zeros
 /// 
 /// Generated from the Sail sources at `tests/types/arch.sail` L66.
-pub const fn zeros<const N: i128>(n: i128) -> BitVector<N> {
+pub const fn zeros<const N: i128>(n: i128) -> BitVector<N> where Width<N>: SupportedWidth {
     sail_zeros(n)
 }
 
 /// hex_bits_backwards
 /// 
 /// Generated from the Sail sources at `tests/types/arch.sail` L77.
-pub fn hex_bits_backwards<const M: i128>(m: i128, str: &'static str) -> BitVector<M> {
+pub fn hex_bits_backwards<const M: i128>(m: i128, str: String) -> BitVector<M> where Width<M>: SupportedWidth {
     parse_hex_bits(m, str)
 }
 
@@ -134,6 +139,7 @@ pub fn validDoubleRegs<const N: usize>(n: i128, regs: [BitVector<5>; N]) -> bool
 /// 
 /// Generated from the Sail sources at `tests/types/arch.sail` L87-90.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum Access_kind<ARCH_AK> {
     AK_ttw(()),
     AK_arch(ARCH_AK)
@@ -143,7 +149,8 @@ pub enum Access_kind<ARCH_AK> {
 /// 
 /// Generated from the Sail sources at `tests/types/arch.sail` L92-98.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
-pub struct Mem_read_request<const N: i128, const VASIZE: i128, PA, ARCH_AK> {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
+pub struct Mem_read_request<const N: i128, const VASIZE: i128, PA, ARCH_AK> where Width<N>: SupportedWidth, Width<VASIZE>: SupportedWidth {
     pub access_kind: Access_kind<ARCH_AK>,
     pub va: Option<BitVector<VASIZE>>,
     pub pa: PA,
@@ -155,6 +162,7 @@ pub struct Mem_read_request<const N: i128, const VASIZE: i128, PA, ARCH_AK> {
 /// 
 /// Generated from the Sail sources at `tests/types/arch.sail` L106.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum Retired {
     RETIRE_SUCCESS,
     RETIRE_FAIL
@@ -170,27 +178,30 @@ pub fn handle_retired(unit_arg: ()) -> Retired {
 /// exception
 /// 
 /// Generated from the Sail sources at `tests/types/arch.sail` L111-114.
-#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[derive(Eq, PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum exception {
-    Error_not_implemented(&'static str),
+    Error_not_implemented(String),
     Error_internal_error(())
 }
 
 /// My_struct
 /// 
 /// Generated from the Sail sources at `tests/types/arch.sail` L116-120.
-#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[derive(Eq, PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub struct My_struct {
     pub field1: BitVector<5>,
     pub field2: i128,
-    pub field3: &'static str,
+    pub field3: String,
 }
 
 /// My_struct_generic
 /// 
 /// Generated from the Sail sources at `tests/types/arch.sail` L122-124.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
-pub struct My_struct_generic<const N: i128> {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
+pub struct My_struct_generic<const N: i128> where Width<N>: SupportedWidth {
     pub foo: BitVector<N>,
 }
 
@@ -215,7 +226,7 @@ pub fn execute_TEST(core_ctx: &mut Core) {
     let a = handle_int(1234);
     let d = handle_retired(());
     let e = handle_union(());
-    let f = hex_bits_backwards(8, "00");
+    let f = hex_bits_backwards(8, String::from("00"));
     let g = pmpMatchAddr(physaddr::Physaddr(BitVector::<64>::new(0b0000000000000000000000000000000011011110101011011011111011101111)));
     if {(f != BitVector::<8>::new(0b00000000))} {
         assert!(false, "failed to parse hex)")
@@ -232,7 +243,7 @@ pub fn execute_TEST(core_ctx: &mut Core) {
     let s: My_struct = My_struct {
         field1: BitVector::<5>::new(0b11111),
         field2: 5,
-        field3: "test"
+        field3: String::from("test")
     };
     let s2: My_struct_generic<4> = My_struct_generic {
         foo: BitVector::<4>::new(0b1010)
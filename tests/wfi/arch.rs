@@ -9,6 +9,7 @@ use softcore_prelude::*;
 /// 
 /// The raw functions translated directly from the specification are available in the `raw` module, whereas higher-level wrappers are implemented as methods on the [Core] struct directly.
 #[derive(Eq, PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub struct Core {
     pub mepc: xlenbits,
     pub sepc: xlenbits,
@@ -22,6 +23,7 @@ pub struct Core {
 }
 
 #[derive(Eq, PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub struct Config {
 
 }
@@ -36,7 +38,7 @@ pub fn _reset_all_registers() {
 /// (operator <_u)
 /// 
 /// Generated from the Sail sources at `tests/wfi/arch.sail` L9.
-pub fn _operator_smaller_u_<const N: i128>(x: BitVector<N>, y: BitVector<N>) -> bool {
+pub fn _operator_smaller_u_<const N: i128>(x: BitVector<N>, y: BitVector<N>) -> bool where Width<N>: SupportedWidth {
     (x.unsigned() < y.unsigned())
 }
 
@@ -50,6 +52,7 @@ pub type priv_level = BitVector<2>;
 /// 
 /// Generated from the Sail sources at `tests/wfi/arch.sail` L21.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum Privilege {
     User,
     Supervisor,
@@ -66,6 +69,7 @@ pub type csreg = BitVector<12>;
 /// 
 /// Generated from the Sail sources at `tests/wfi/arch.sail` L39-63.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub struct Mstatus {
     pub bits: BitVector<64>,
 }
@@ -104,6 +108,7 @@ pub fn platform_wfi(core_ctx: &mut Core, unit_arg: ()) {
 /// 
 /// Generated from the Sail sources at `tests/wfi/arch.sail` L87.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum Retired {
     RETIRE_SUCCESS,
     RETIRE_FAIL
@@ -113,6 +118,7 @@ pub enum Retired {
 /// 
 /// Generated from the Sail sources at `tests/wfi/arch.sail` L89.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(crate = "softcore_prelude::serde"))]
 pub enum ast {
     WFI(())
 }